@@ -0,0 +1,47 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+
+/// Stable, machine-readable error envelope returned by every handler instead
+/// of a bare status code, so API clients can branch on `error_code` rather
+/// than guessing from the HTTP status alone. Shared across services so this
+/// envelope shape - and the error link convention - can't drift between
+/// them; each service adds its own error constructors on top via a local
+/// extension trait (see e.g. `indexing-service/src/models/error.rs`).
+#[derive(Debug, Serialize)]
+pub struct ApiError {
+    #[serde(skip)]
+    pub status: StatusCode,
+    pub message: String,
+    #[serde(rename = "errorCode")]
+    pub error_code: &'static str,
+    #[serde(rename = "errorType")]
+    pub error_type: &'static str,
+    #[serde(rename = "errorLink")]
+    pub error_link: String,
+}
+
+impl ApiError {
+    pub fn new(
+        status: StatusCode,
+        error_code: &'static str,
+        error_type: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            error_code,
+            error_type,
+            error_link: format!("https://docs.example.com/errors/{}", error_code),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        (status, Json(self)).into_response()
+    }
+}
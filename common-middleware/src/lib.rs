@@ -0,0 +1,147 @@
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use http::{HeaderName, HeaderValue, Method, Request, Response};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+use tracing::{info_span, warn, Instrument};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+type BoxFuture<T> = Pin<Box<dyn std::future::Future<Output = T> + Send>>;
+
+/// Carries the generated id in request extensions so handlers can read it
+/// back out (e.g. to echo it into a downstream call).
+#[derive(Clone)]
+pub struct RequestId(pub String);
+
+/// Generates a UUID v4 per inbound request, attaches it to a tracing span
+/// covering the whole request/response so every `info!`/`error!` underneath
+/// carries it, echoes it on the `x-request-id` response header, and logs
+/// completion (or cancellation, if the client disconnects before a response
+/// is produced). Layer this alongside `TraceLayer` rather than in place of
+/// it - this middleware is about correlation, `TraceLayer` about tracing
+/// spans per se.
+#[derive(Clone, Default)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for RequestIdService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        // Reuse an inbound x-request-id if the caller already set one (e.g.
+        // ControlModule propagating a single book's id across services),
+        // so one book's journey stays under one id end to end.
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let remote_addr = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        req.extensions_mut().insert(RequestId(request_id.clone()));
+
+        let span = info_span!(
+            "http_request",
+            request_id = %request_id,
+            method = %method,
+            path = %path,
+            remote_addr = %remote_addr,
+        );
+
+        let mut inner = self.inner.clone();
+        let start = Instant::now();
+        let response_id = request_id.clone();
+        let mut cancel_guard = CancelGuard {
+            request_id,
+            method,
+            path,
+            start,
+            completed: false,
+        };
+
+        let fut = async move {
+            let result = inner.call(req).await;
+            cancel_guard.completed = true;
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+
+            match &result {
+                Ok(response) => {
+                    tracing::info!(status = response.status().as_u16(), elapsed_ms, "request completed");
+                }
+                Err(_) => {
+                    tracing::error!(elapsed_ms, "request failed");
+                }
+            }
+
+            result.map(|mut response| {
+                if let Ok(value) = HeaderValue::from_str(&response_id) {
+                    response
+                        .headers_mut()
+                        .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                }
+                response
+            })
+        }
+        .instrument(span);
+
+        Box::pin(fut)
+    }
+}
+
+/// Logs a cancellation warning if the request future is dropped (client
+/// disconnect, timeout) before `call`'s future marks it completed.
+struct CancelGuard {
+    request_id: String,
+    method: Method,
+    path: String,
+    start: Instant,
+    completed: bool,
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        if !self.completed {
+            warn!(
+                request_id = %self.request_id,
+                method = %self.method,
+                path = %self.path,
+                elapsed_ms = self.start.elapsed().as_millis() as u64,
+                "request cancelled before completion"
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use regex::Regex;
+use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::HashSet;
+
+/// Stop words dropped before stemming; languages without a curated list below
+/// fall back to the English list, since the bulk of the indexed corpus is
+/// English-language Gutenberg text.
+const STOPWORDS_EN: &[&str] = &[
+    "the", "a", "an", "and", "or", "but", "of", "to", "in", "on", "for", "with", "as", "is",
+    "was", "were", "are", "be", "been", "being", "by", "at", "from", "that", "this", "it", "its",
+    "into", "than", "then", "so", "such", "not", "no", "nor", "has", "have", "had",
+];
+
+const STOPWORDS_FR: &[&str] = &[
+    "le", "la", "les", "un", "une", "des", "de", "du", "et", "ou", "mais", "dans", "sur", "pour",
+    "avec", "est", "sont", "que", "qui", "ce", "cette", "ces",
+];
+
+const STOPWORDS_DE: &[&str] = &[
+    "der", "die", "das", "ein", "eine", "und", "oder", "aber", "in", "auf", "fur", "mit", "ist",
+    "sind", "dass", "diese", "nicht",
+];
+
+const STOPWORDS_ES: &[&str] = &[
+    "el", "la", "los", "las", "un", "una", "y", "o", "pero", "de", "en", "por", "para", "con",
+    "es", "son", "que", "no",
+];
+
+fn stopwords_for(language: &str) -> &'static [&'static str] {
+    match language.to_lowercase().as_str() {
+        "french" | "fr" => STOPWORDS_FR,
+        "german" | "de" => STOPWORDS_DE,
+        "spanish" | "es" => STOPWORDS_ES,
+        _ => STOPWORDS_EN,
+    }
+}
+
+/// CJK scripts have no whitespace between words, so the Latin `\p{L}+` regex
+/// path below would return one giant token per run of CJK characters instead
+/// of individual words.
+pub fn is_cjk_language(language: &str) -> bool {
+    matches!(
+        language.to_lowercase().as_str(),
+        "chinese" | "zh" | "japanese" | "ja" | "korean" | "ko"
+    )
+}
+
+/// Seed dictionary for forward maximum-matching segmentation. A real
+/// deployment would load a proper lexicon (e.g. CEDICT/IPADIC); this covers
+/// a handful of common words so CJK text segments into more than
+/// one-token-per-character.
+const CJK_SEED_DICTIONARY: &[&str] = &[
+    "中国", "日本", "北京", "东京", "你好", "谢谢", "学生", "老师", "图书馆", "大学", "日本語",
+    "中文", "日本人", "东京都",
+];
+
+const MAX_CJK_WORD_CHARS: usize = 4;
+
+/// Forward maximum-matching segmentation: at each position, tries the
+/// longest dictionary entry starting there, falling back to a single
+/// character when nothing matches.
+fn segment_cjk(text: &str) -> Vec<String> {
+    let dictionary: HashSet<&str> = CJK_SEED_DICTIONARY.iter().copied().collect();
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let max_len = MAX_CJK_WORD_CHARS.min(chars.len() - i);
+        let matched = (1..=max_len).rev().find_map(|len| {
+            let candidate: String = chars[i..i + len].iter().collect();
+            dictionary.contains(candidate.as_str()).then_some(candidate)
+        });
+
+        match matched {
+            Some(word) => {
+                i += word.chars().count();
+                tokens.push(word);
+            }
+            None => {
+                tokens.push(chars[i].to_string());
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Maps a `BookMetadata.language` value (usually the English name Gutenberg
+/// puts in its header, e.g. "French") to the matching Snowball algorithm,
+/// falling back to English stemming for anything unrecognized.
+pub fn algorithm_for(language: &str) -> Algorithm {
+    match language.to_lowercase().as_str() {
+        "french" | "fr" => Algorithm::French,
+        "german" | "de" => Algorithm::German,
+        "spanish" | "es" => Algorithm::Spanish,
+        "italian" | "it" => Algorithm::Italian,
+        "portuguese" | "pt" => Algorithm::Portuguese,
+        "dutch" | "nl" => Algorithm::Dutch,
+        "swedish" | "sv" => Algorithm::Swedish,
+        "norwegian" | "no" => Algorithm::Norwegian,
+        "danish" | "da" => Algorithm::Danish,
+        "finnish" | "fi" => Algorithm::Finnish,
+        "russian" | "ru" => Algorithm::Russian,
+        _ => Algorithm::English,
+    }
+}
+
+/// Lowercases, splits on Unicode letter runs (or segments CJK scripts
+/// dictionary-style), drops stop words for `language`, and stems each
+/// surviving term with the matching Snowball algorithm so inflected forms
+/// ("running"/"runs"/"ran") collapse to one index term. Positions are token
+/// indices into the surviving term stream (not character offsets).
+///
+/// Shared by `indexing-service` (tokenizing book bodies/titles for storage)
+/// and `search-service` (tokenizing queries) so the two can never drift out
+/// of sync the way two hand-duplicated copies would.
+pub fn tokenize_with_positions(text: &str, language: &str) -> Vec<(String, usize)> {
+    tokenize_with_options(text, language, true, None)
+}
+
+/// Same as `tokenize_with_positions`, but lets callers disable the stemming
+/// pass or override the stopword set - e.g. to reproduce the pre-stemming
+/// tokenizer behavior (`stem: false, stopwords: Some(&HashSet::new())`) for
+/// comparison or benchmarking.
+pub fn tokenize_with_options(
+    text: &str,
+    language: &str,
+    stem: bool,
+    stopwords: Option<&HashSet<String>>,
+) -> Vec<(String, usize)> {
+    if is_cjk_language(language) {
+        // No whitespace delimiters and no Snowball stemmer for these
+        // languages, so dictionary segmentation stands in for both the
+        // regex split and the stemming pass.
+        return segment_cjk(&text.to_lowercase())
+            .into_iter()
+            .enumerate()
+            .map(|(position, term)| (term, position))
+            .collect();
+    }
+
+    let word_re = Regex::new(r"\p{L}+").unwrap();
+    let default_stopwords = stopwords_for(language);
+    let is_stopword = |word: &str| match stopwords {
+        Some(custom) => custom.contains(word),
+        None => default_stopwords.contains(&word),
+    };
+    let stemmer = Stemmer::create(algorithm_for(language));
+
+    word_re
+        .find_iter(&text.to_lowercase())
+        .map(|m| m.as_str())
+        .filter(|word| word.chars().count() > 2)
+        .filter(|word| !is_stopword(word))
+        .map(|word| {
+            if stem {
+                stemmer.stem(word).into_owned()
+            } else {
+                word.to_string()
+            }
+        })
+        .enumerate()
+        .map(|(position, term)| (term, position))
+        .collect()
+}
+
+pub fn tokenize_text(text: &str, language: &str) -> HashSet<String> {
+    tokenize_with_positions(text, language)
+        .into_iter()
+        .map(|(term, _)| term)
+        .collect()
+}
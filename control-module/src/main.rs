@@ -1,20 +1,35 @@
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+#[derive(Debug, Deserialize)]
+struct ProgressEvent {
+    book_id: u32,
+    words_indexed: usize,
+    status: String,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-struct IngestResponse {
+struct EnqueueResponse {
+    task_id: String,
     book_id: u32,
     status: String,
-    path: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct StatusResponse {
+struct TaskStatusResponse {
+    task_id: String,
     book_id: u32,
     status: String,
+    path: Option<String>,
+    error: Option<String>,
+    updated_at: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,19 +97,25 @@ impl ControlModule {
     async fn ingest_book(
         &self,
         book_id: u32,
-    ) -> Result<IngestResponse, Box<dyn std::error::Error>> {
-        info!("Ingesting book {}", book_id);
+        request_id: &str,
+    ) -> Result<EnqueueResponse, Box<dyn std::error::Error>> {
+        info!("Enqueueing ingestion for book {}", book_id);
 
         let url = format!("{}/ingest/{}", INGESTION_SERVICE_URL, book_id);
-        let response = self.client.post(&url).send().await?;
+        let response = self
+            .client
+            .post(&url)
+            .header(REQUEST_ID_HEADER, request_id)
+            .send()
+            .await?;
 
         if response.status().is_success() {
-            let ingest_response: IngestResponse = response.json().await?;
+            let enqueue_response: EnqueueResponse = response.json().await?;
             info!(
-                "Successfully ingested book {}: {}",
-                book_id, ingest_response.status
+                "Book {} enqueued as task {}",
+                book_id, enqueue_response.task_id
             );
-            Ok(ingest_response)
+            Ok(enqueue_response)
         } else {
             let error_msg = format!("Failed to ingest book {}: {}", book_id, response.status());
             error!("{}", error_msg);
@@ -102,31 +123,69 @@ impl ControlModule {
         }
     }
 
-    async fn check_ingestion_status(
+    /// Polls an ingest task until it reaches a terminal state, returning the
+    /// datalake path on success. The download runs in the background on the
+    /// ingestion service, so this is how the pipeline waits for it. Gives up
+    /// with an error after `MAX_POLLS` polls 500ms apart (about 30s).
+    async fn wait_for_ingest_task(
         &self,
-        book_id: u32,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        let url = format!("{}/ingest/status/{}", INGESTION_SERVICE_URL, book_id);
-        let response = self.client.get(&url).send().await?;
-
-        if response.status().is_success() {
-            let status_response: StatusResponse = response.json().await?;
-            Ok(status_response.status == "available")
-        } else {
-            Ok(false)
+        task_id: &str,
+        request_id: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        const MAX_POLLS: u32 = 60;
+        let url = format!("{}/ingest/task/{}", INGESTION_SERVICE_URL, task_id);
+
+        for _ in 0..MAX_POLLS {
+            let response = self
+                .client
+                .get(&url)
+                .header(REQUEST_ID_HEADER, request_id)
+                .send()
+                .await?;
+            if response.status().is_success() {
+                let task: TaskStatusResponse = response.json().await?;
+                match task.status.as_str() {
+                    "succeeded" => {
+                        return task
+                            .path
+                            .ok_or_else(|| "task succeeded without a path".into())
+                    }
+                    "failed" => {
+                        return Err(format!(
+                            "task {} failed: {}",
+                            task_id,
+                            task.error.unwrap_or_else(|| "unknown error".to_string())
+                        )
+                        .into())
+                    }
+                    _ => {}
+                }
+            }
+            sleep(Duration::from_millis(500)).await;
         }
+
+        Err(format!("task {} did not complete in time", task_id).into())
     }
 
-    async fn index_book(&self, book_id: u32) -> Result<IndexResponse, Box<dyn std::error::Error>> {
+    async fn index_book(
+        &self,
+        book_id: u32,
+        request_id: &str,
+    ) -> Result<IndexResponse, Box<dyn std::error::Error>> {
         info!("Indexing book {}", book_id);
 
         let url = format!("{}/index/update/{}", INDEXING_SERVICE_URL, book_id);
-        let response = self.client.post(&url).send().await?;
+        let response = self
+            .client
+            .post(&url)
+            .header(REQUEST_ID_HEADER, request_id)
+            .send()
+            .await?;
 
         if response.status().is_success() {
             let index_response: IndexResponse = response.json().await?;
             info!(
-                "Successfully indexed book {}: {}",
+                "Book {} accepted by the index queue: {}",
                 book_id, index_response.status
             );
             Ok(index_response)
@@ -150,34 +209,33 @@ impl ControlModule {
     }
 
     async fn process_book(&self, book_id: u32) -> Result<(), Box<dyn std::error::Error>> {
-        info!("🚀 Starting processing pipeline for book {}", book_id);
-
-        info!("📥 Step 1: Ingesting book {}", book_id);
-        let ingest_response = self.ingest_book(book_id).await?;
-
-        info!("⏳ Step 2: Waiting for ingestion confirmation...");
-        sleep(Duration::from_millis(500)).await;
-
-        info!("✅ Step 3: Verifying ingestion status...");
-        if !self.check_ingestion_status(book_id).await? {
-            return Err(format!(
-                "Book {} ingestion verification failed - status not 'available'",
-                book_id
-            )
-            .into());
-        }
+        // One id for the whole pipeline, so every service's logs for this
+        // book's journey can be grepped out with a single value.
+        let request_id = Uuid::new_v4().to_string();
         info!(
-            "✅ Book {} successfully ingested at: {}",
-            book_id, ingest_response.path
+            "🚀 Starting processing pipeline for book {} (request_id={})",
+            book_id, request_id
         );
 
-        info!("📊 Step 4: Indexing book {}", book_id);
-        let index_response = self.index_book(book_id).await?;
+        info!("📥 Step 1: Enqueueing ingestion for book {}", book_id);
+        let enqueue_response = self.ingest_book(book_id, &request_id).await?;
+
+        info!("⏳ Step 2: Waiting for download to complete...");
+        let path = self
+            .wait_for_ingest_task(&enqueue_response.task_id, &request_id)
+            .await?;
+        info!("✅ Book {} successfully ingested at: {}", book_id, path);
+
+        info!("📊 Step 3: Enqueueing book {} for indexing", book_id);
+        let index_response = self.index_book(book_id, &request_id).await?;
 
-        info!("✅ Step 5: Verifying indexing completion...");
-        if index_response.status != "indexed" {
+        // Indexing now runs on the indexing service's background queue
+        // (with same-book updates coalesced), so this step only confirms
+        // the book was accepted - run with `--watch` to observe the
+        // `indexed`/`failed` transition via SSE once the queue flushes it.
+        if index_response.status != "queued" {
             return Err(format!(
-                "Book {} indexing verification failed - status: {}",
+                "Book {} was not accepted by the index queue - status: {}",
                 book_id, index_response.status
             )
             .into());
@@ -227,6 +285,40 @@ impl ControlModule {
             }
         }
     }
+
+    /// Subscribes to the indexing service's SSE progress stream instead of
+    /// polling `/ingest/list` - push-based, so operators see each book's
+    /// `metadata_stored`/`indexing`/`indexed` transitions as they happen
+    /// rather than only a periodic count.
+    async fn watch_mode(&self) -> Result<(), Box<dyn std::error::Error>> {
+        info!("Watching indexing progress via SSE...");
+
+        let url = format!("{}/index/events", INDEXING_SERVICE_URL);
+        let response = self.client.get(&url).send().await?;
+        let mut stream = response.bytes_stream();
+        let mut buffer = String::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+            while let Some(newline_pos) = buffer.find('\n') {
+                let line = buffer[..newline_pos].trim_end_matches('\r').to_string();
+                buffer.drain(..=newline_pos);
+
+                if let Some(payload) = line.strip_prefix("data: ") {
+                    if let Ok(event) = serde_json::from_str::<ProgressEvent>(payload) {
+                        info!(
+                            "📡 book {} -> {} ({} words indexed)",
+                            event.book_id, event.status, event.words_indexed
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[tokio::main]
@@ -246,6 +338,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if args.len() > 1 && args[1] == "--continuous" {
         // Run in continuous monitoring mode
         control.continuous_mode().await?;
+    } else if args.len() > 1 && args[1] == "--watch" {
+        // Stream live progress via SSE instead of polling
+        control.watch_mode().await?;
     } else if args.len() > 1 {
         // Process specific book IDs from command line
         let book_ids: Result<Vec<u32>, _> = args[1..].iter().map(|s| s.parse()).collect();
@@ -255,7 +350,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             Err(e) => {
                 error!("Invalid book IDs provided: {}", e);
-                info!("Usage: control-module [book_id1] [book_id2] ... or --continuous");
+                info!("Usage: control-module [book_id1] [book_id2] ... or --continuous or --watch");
                 std::process::exit(1);
             }
         }
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use regex::Regex;
+use rust_stemmers::{Algorithm, Stemmer};
 use std::collections::HashSet;
 
 fn tokenize_text(text: &str) -> HashSet<String> {
@@ -10,6 +11,26 @@ fn tokenize_text(text: &str) -> HashSet<String> {
         .collect()
 }
 
+const STOPWORDS_EN: &[&str] = &[
+    "the", "a", "an", "and", "or", "but", "of", "to", "in", "on", "for", "with", "as", "is",
+    "was", "were", "are", "be", "been", "being", "by", "at", "from", "that", "this", "it", "its",
+    "into", "than", "then", "so", "such", "not", "no", "nor", "has", "have", "had",
+];
+
+/// Mirrors `indexing-service`'s English stopword-filtering + Porter-stemming
+/// tokenizer, so this benchmark measures the added cost over the plain
+/// `tokenize_text` case above.
+fn tokenize_text_stemmed(text: &str) -> HashSet<String> {
+    let re = Regex::new(r"\p{L}+").unwrap();
+    let stemmer = Stemmer::create(Algorithm::English);
+    re.find_iter(&text.to_lowercase())
+        .map(|m| m.as_str())
+        .filter(|word| word.chars().count() > 2)
+        .filter(|word| !STOPWORDS_EN.contains(word))
+        .map(|word| stemmer.stem(word).into_owned())
+        .collect()
+}
+
 fn extract_metadata_from_header(header_content: &str) -> (String, String, String) {
     let title_re = Regex::new(r"(?i)title:\s*(.+)").unwrap();
     let author_re = Regex::new(r"(?i)author:\s*(.+)").unwrap();
@@ -49,6 +70,14 @@ fn benchmark_tokenize_text_large(c: &mut Criterion) {
     });
 }
 
+fn benchmark_tokenize_text_stemmed(c: &mut Criterion) {
+    let large_text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. ".repeat(1000);
+
+    c.bench_function("tokenize_text_stemmed", |b| {
+        b.iter(|| tokenize_text_stemmed(black_box(&large_text)))
+    });
+}
+
 fn benchmark_extract_metadata(c: &mut Criterion) {
     let sample_header = r#"
 Title: Pride and Prejudice
@@ -82,6 +111,7 @@ criterion_group!(
     benches,
     benchmark_tokenize_text,
     benchmark_tokenize_text_large,
+    benchmark_tokenize_text_stemmed,
     benchmark_extract_metadata,
     benchmark_full_processing
 );
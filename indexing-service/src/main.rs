@@ -1,9 +1,12 @@
+use axum::extract::FromRef;
 use axum::{
     routing::{get, post},
     Router,
 };
 use std::sync::Arc;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
+use tower_http::decompression::RequestDecompressionLayer;
 use tower_http::trace::TraceLayer;
 use tracing::{error, info};
 
@@ -12,14 +15,69 @@ mod routes;
 mod services;
 mod utils;
 
+use common_middleware::RequestIdLayer;
 use models::storage::{PostgresBackend, RedisBackend, StorageBackend};
 use routes::{
+    events::index_events,
     health::health_check,
-    index::{get_index_status, index_book, rebuild_index},
+    index::{get_index_status, index_book, lookup_word, rebuild_index},
+    metrics::metrics_handler,
 };
+use services::metrics::{InstrumentedBackend, Metrics};
+use services::progress::ProgressPublisher;
+use services::queue::{run_worker, IndexJob, PendingBooks};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use utils::tracking_allocator::TrackingAllocator;
+
+// Tracks live/peak allocated bytes process-wide, so `/index/rebuild` can
+// report real memory use instead of an estimate based on book size.
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
 
 type Backend = Arc<dyn StorageBackend + Send + Sync>;
 
+#[derive(Clone)]
+struct AppState {
+    backend: Backend,
+    metrics: Arc<Metrics>,
+    progress: Arc<ProgressPublisher>,
+    index_queue: mpsc::Sender<IndexJob>,
+    pending_books: PendingBooks,
+}
+
+impl FromRef<AppState> for Backend {
+    fn from_ref(state: &AppState) -> Self {
+        state.backend.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Metrics> {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<ProgressPublisher> {
+    fn from_ref(state: &AppState) -> Self {
+        state.progress.clone()
+    }
+}
+
+impl FromRef<AppState> for mpsc::Sender<IndexJob> {
+    fn from_ref(state: &AppState) -> Self {
+        state.index_queue.clone()
+    }
+}
+
+impl FromRef<AppState> for PendingBooks {
+    fn from_ref(state: &AppState) -> Self {
+        state.pending_books.clone()
+    }
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt()
@@ -56,14 +114,49 @@ async fn main() {
     }
     info!("Storage backend connection successful");
 
+    let metrics = Arc::new(Metrics::new());
+    let backend: Backend = Arc::new(InstrumentedBackend::new(backend, metrics.clone()));
+
+    // Progress pub/sub always talks to Redis directly, regardless of which
+    // StorageBackend is configured for the index itself.
+    let progress_redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://redis:6379".to_string());
+    let progress = Arc::new(
+        ProgressPublisher::new(&progress_redis_url).expect("Failed to connect progress publisher to Redis"),
+    );
+
+    let pending_books: PendingBooks = Arc::new(Mutex::new(HashSet::new()));
+    let (index_queue, index_jobs) = mpsc::channel::<IndexJob>(100);
+    tokio::spawn(run_worker(
+        index_jobs,
+        pending_books.clone(),
+        backend.clone(),
+        progress.clone(),
+        metrics.clone(),
+    ));
+
+    let app_state = AppState {
+        backend,
+        metrics,
+        progress,
+        index_queue,
+        pending_books,
+    };
+
     let app = Router::new()
         .route("/status", get(health_check))
+        .route("/metrics", get(metrics_handler))
         .route("/index/update/:book_id", post(index_book))
         .route("/index/rebuild", post(rebuild_index))
         .route("/index/status", get(get_index_status))
+        .route("/index/word/:term", get(lookup_word))
+        .route("/index/events", get(index_events))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
-        .with_state(backend);
+        .layer(RequestIdLayer)
+        .layer(CompressionLayer::new())
+        .layer(RequestDecompressionLayer::new())
+        .with_state(app_state);
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "7002".to_string());
     let addr = format!("0.0.0.0:{}", port);
@@ -71,5 +164,10 @@ async fn main() {
     info!("Indexing service starting on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
\ No newline at end of file
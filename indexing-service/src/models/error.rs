@@ -0,0 +1,40 @@
+use axum::http::StatusCode;
+pub use common_error::ApiError;
+
+/// This service's error constructors, added via an extension trait since
+/// `ApiError` itself now lives in `common_error` and inherent impls can only
+/// be added from the crate that defines the type.
+pub trait ApiErrorExt {
+    fn book_not_found(book_id: u32) -> Self;
+    fn index_backend_unavailable(source: impl std::fmt::Display) -> Self;
+    fn index_read_failed(source: impl std::fmt::Display) -> Self;
+}
+
+impl ApiErrorExt for ApiError {
+    fn book_not_found(book_id: u32) -> Self {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            "book_not_found",
+            "not_found",
+            format!("Book {} files not found in the datalake", book_id),
+        )
+    }
+
+    fn index_backend_unavailable(source: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "index_backend_unavailable",
+            "backend_error",
+            format!("Index storage backend error: {}", source),
+        )
+    }
+
+    fn index_read_failed(source: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "index_read_failed",
+            "io_error",
+            format!("Failed to read the on-disk inverted index: {}", source),
+        )
+    }
+}
@@ -16,6 +16,7 @@ pub struct IndexResponse {
 pub struct RebuildResponse {
     pub books_processed: usize,
     pub elapsed_time: String,
+    pub peak_memory_mb: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,4 +24,18 @@ pub struct IndexStatusResponse {
     pub books_indexed: usize,
     pub last_update: String,
     pub index_size_mb: f64,
+    pub queue_depth: usize,
+    pub peak_memory_mb: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordPosting {
+    pub book_id: u32,
+    pub term_frequency: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordLookupResponse {
+    pub term: String,
+    pub postings: Vec<WordPosting>,
 }
@@ -1,11 +1,15 @@
 use async_trait::async_trait;
+use deadpool_redis::{Config as RedisConfig, Pool as RedisPool, PoolConfig, Runtime};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::collections::HashSet;
+use std::time::Duration;
 use thiserror::Error;
 use tracing::error;
 
+use crate::utils::blob_compression;
+
 #[derive(Error, Debug)]
 pub enum StorageError {
     #[error("Redis error: {0}")]
@@ -35,24 +39,68 @@ pub trait StorageBackend {
     async fn get_book_metadata(&self, book_id: u32) -> Result<Option<BookMetadata>, StorageError>;
     async fn is_book_indexed(&self, book_id: u32) -> Result<bool, StorageError>;
     async fn get_indexed_books(&self) -> Result<HashSet<u32>, StorageError>;
-    async fn add_word_to_index(&self, word: &str, book_id: u32) -> Result<(), StorageError>;
+    async fn add_word_to_index(
+        &self,
+        word: &str,
+        book_id: u32,
+        term_frequency: usize,
+    ) -> Result<(), StorageError>;
     async fn search_word(&self, word: &str) -> Result<HashSet<u32>, StorageError>;
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError>;
     async fn get_stats(&self) -> Result<(usize, usize), StorageError>; // (total_books, unique_words)
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError>;
+    async fn get_all_words(&self) -> Result<Vec<String>, StorageError>;
+    async fn store_vocabulary_fst(&self, fst_bytes: Vec<u8>) -> Result<(), StorageError>;
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError>;
+    async fn store_correction_index(&self, index_bytes: Vec<u8>) -> Result<(), StorageError>;
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError>;
     async fn test_connection(&self) -> Result<(), StorageError>;
 }
 
 pub struct RedisBackend {
-    client: redis::Client,
+    pool: RedisPool,
+}
+
+/// Bulk indexing issues one `add_word_to_index` call per distinct word in a
+/// book, so reconnecting per call (the old behavior) made connection setup
+/// the bottleneck. One pooled connection per core keeps that word-by-word
+/// loop from queuing behind itself; a 5s checkout timeout surfaces a starved
+/// pool as an error instead of hanging the request.
+fn default_pool_size() -> usize {
+    num_cpus::get()
 }
 
+const DEFAULT_POOL_TIMEOUT_SECS: u64 = 5;
+
 impl RedisBackend {
     pub fn new(redis_url: &str) -> Result<Self, StorageError> {
-        let client = redis::Client::open(redis_url)?;
-        Ok(Self { client })
+        let pool_size = std::env::var("REDIS_POOL_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(default_pool_size);
+        let wait_timeout_secs = std::env::var("REDIS_POOL_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_POOL_TIMEOUT_SECS);
+
+        let mut pool_config = PoolConfig::new(pool_size);
+        pool_config.timeouts.wait = Some(Duration::from_secs(wait_timeout_secs));
+
+        let mut config = RedisConfig::from_url(redis_url);
+        config.pool = Some(pool_config);
+
+        let pool = config
+            .create_pool(Some(Runtime::Tokio1))
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(Self { pool })
     }
 
-    pub async fn get_connection(&self) -> Result<redis::aio::MultiplexedConnection, StorageError> {
-        Ok(self.client.get_multiplexed_async_connection().await?)
+    pub async fn get_connection(&self) -> Result<deadpool_redis::Connection, StorageError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
     }
 }
 
@@ -62,10 +110,13 @@ impl StorageBackend for RedisBackend {
         let mut conn = self.get_connection().await?;
 
         let key = format!("book:{}:metadata", metadata.book_id);
-        let value = serde_json::to_string(metadata)?;
+        let value = serde_json::to_vec(metadata)?;
+        let value = blob_compression::compress(blob_compression::BlobCodec::from_env(), &value);
 
-        conn.set::<_, _, ()>(&key, &value).await?;
+        conn.set::<_, _, ()>(&key, value).await?;
         conn.incr::<_, _, ()>("stats:total_books", 1).await?;
+        conn.incr::<_, _, ()>("stats:total_word_count", metadata.word_count as i64)
+            .await?;
 
         Ok(())
     }
@@ -74,11 +125,12 @@ impl StorageBackend for RedisBackend {
         let mut conn = self.get_connection().await?;
 
         let key = format!("book:{}:metadata", book_id);
-        let value: Option<String> = conn.get(&key).await?;
+        let value: Option<Vec<u8>> = conn.get(&key).await?;
 
         match value {
-            Some(json_str) => {
-                let metadata: BookMetadata = serde_json::from_str(&json_str)?;
+            Some(bytes) => {
+                let bytes = blob_compression::decompress(&bytes);
+                let metadata: BookMetadata = serde_json::from_slice(&bytes)?;
                 Ok(Some(metadata))
             }
             None => Ok(None),
@@ -115,13 +167,22 @@ impl StorageBackend for RedisBackend {
         Ok(book_ids)
     }
 
-    async fn add_word_to_index(&self, word: &str, book_id: u32) -> Result<(), StorageError> {
+    async fn add_word_to_index(
+        &self,
+        word: &str,
+        book_id: u32,
+        term_frequency: usize,
+    ) -> Result<(), StorageError> {
         let mut conn = self.get_connection().await?;
 
         let word_key = format!("word:{}", word);
         conn.sadd::<_, _, ()>(&word_key, book_id).await?;
         conn.sadd::<_, _, ()>("stats:all_words", word).await?;
 
+        let tf_key = format!("word:{}:tf", word);
+        conn.hset::<_, _, _, ()>(&tf_key, book_id, term_frequency as i64)
+            .await?;
+
         Ok(())
     }
 
@@ -134,6 +195,15 @@ impl StorageBackend for RedisBackend {
         Ok(book_ids.into_iter().collect())
     }
 
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let tf_key = format!("word:{}:tf", word);
+        let tf: Option<i64> = conn.hget(&tf_key, book_id).await?;
+
+        Ok(tf.unwrap_or(0).max(0) as usize)
+    }
+
     async fn get_stats(&self) -> Result<(usize, usize), StorageError> {
         let mut conn = self.get_connection().await?;
 
@@ -143,6 +213,51 @@ impl StorageBackend for RedisBackend {
         Ok((total_books.unwrap_or(0), unique_words))
     }
 
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let total_books: Option<usize> = conn.get("stats:total_books").await?;
+        let total_word_count: Option<usize> = conn.get("stats:total_word_count").await?;
+
+        let total_books = total_books.unwrap_or(0);
+        if total_books == 0 {
+            return Ok(0.0);
+        }
+
+        Ok(total_word_count.unwrap_or(0) as f64 / total_books as f64)
+    }
+
+    async fn get_all_words(&self) -> Result<Vec<String>, StorageError> {
+        let mut conn = self.get_connection().await?;
+        let words: Vec<String> = conn.smembers("stats:all_words").await?;
+        Ok(words)
+    }
+
+    async fn store_vocabulary_fst(&self, fst_bytes: Vec<u8>) -> Result<(), StorageError> {
+        let mut conn = self.get_connection().await?;
+        conn.set::<_, _, ()>("index:vocabulary_fst", fst_bytes).await?;
+        Ok(())
+    }
+
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let mut conn = self.get_connection().await?;
+        let bytes: Option<Vec<u8>> = conn.get("index:vocabulary_fst").await?;
+        Ok(bytes)
+    }
+
+    async fn store_correction_index(&self, index_bytes: Vec<u8>) -> Result<(), StorageError> {
+        let mut conn = self.get_connection().await?;
+        conn.set::<_, _, ()>("index:correction_index", index_bytes)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let mut conn = self.get_connection().await?;
+        let bytes: Option<Vec<u8>> = conn.get("index:correction_index").await?;
+        Ok(bytes)
+    }
+
     async fn test_connection(&self) -> Result<(), StorageError> {
         let mut conn = self.get_connection().await?;
         let _: Option<String> = conn.get("__connection_test__").await?;
@@ -157,51 +272,16 @@ pub struct PostgresBackend {
 impl PostgresBackend {
     pub async fn new(database_url: &str) -> Result<Self, StorageError> {
         let pool = PgPool::connect(database_url).await?;
-
-        // Initialize tables
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS books (
-                book_id INTEGER PRIMARY KEY,
-                title TEXT,
-                author TEXT,
-                language VARCHAR(10),
-                year INTEGER,
-                word_count INTEGER,
-                unique_words INTEGER,
-                indexed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS word_index (
-                word VARCHAR,
-                book_id INTEGER,
-                PRIMARY KEY (word, book_id)
-            )
-            "#,
-        )
-        .execute(&pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_word_index_word ON word_index(word)
-            "#,
-        )
-        .execute(&pool)
-        .await?;
-
+        crate::models::migrations::run_migrations(&pool).await?;
         Ok(Self { pool })
     }
 }
 
 #[async_trait]
 impl StorageBackend for PostgresBackend {
+    // Metadata here lives in typed relational columns rather than a single
+    // serialized blob, so there's no opaque value to run `blob_compression`
+    // over the way `RedisBackend` does - the columns are already compact.
     async fn store_book_metadata(&self, metadata: &BookMetadata) -> Result<(), StorageError> {
         sqlx::query(
             r#"
@@ -277,12 +357,21 @@ impl StorageBackend for PostgresBackend {
         Ok(book_ids)
     }
 
-    async fn add_word_to_index(&self, word: &str, book_id: u32) -> Result<(), StorageError> {
+    async fn add_word_to_index(
+        &self,
+        word: &str,
+        book_id: u32,
+        term_frequency: usize,
+    ) -> Result<(), StorageError> {
         sqlx::query(
-            "INSERT INTO word_index (word, book_id) VALUES ($1, $2) ON CONFLICT (word, book_id) DO NOTHING"
+            r#"
+            INSERT INTO word_index (word, book_id, tf) VALUES ($1, $2, $3)
+            ON CONFLICT (word, book_id) DO UPDATE SET tf = EXCLUDED.tf
+            "#,
         )
         .bind(word)
         .bind(book_id as i32)
+        .bind(term_frequency as i32)
         .execute(&self.pool)
         .await?;
 
@@ -303,6 +392,16 @@ impl StorageBackend for PostgresBackend {
         Ok(book_ids)
     }
 
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError> {
+        let row = sqlx::query("SELECT tf FROM word_index WHERE word = $1 AND book_id = $2")
+            .bind(word)
+            .bind(book_id as i32)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get::<i32, _>("tf") as usize).unwrap_or(0))
+    }
+
     async fn get_stats(&self) -> Result<(usize, usize), StorageError> {
         let total_books = sqlx::query("SELECT COUNT(*) as count FROM books")
             .fetch_one(&self.pool)
@@ -317,6 +416,66 @@ impl StorageBackend for PostgresBackend {
         Ok((total_books, unique_words))
     }
 
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError> {
+        let row = sqlx::query("SELECT COALESCE(AVG(word_count)::float8, 0) as avgdl FROM books")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get::<f64, _>("avgdl"))
+    }
+
+    async fn get_all_words(&self) -> Result<Vec<String>, StorageError> {
+        let rows = sqlx::query("SELECT DISTINCT word FROM word_index")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("word")).collect())
+    }
+
+    async fn store_vocabulary_fst(&self, fst_bytes: Vec<u8>) -> Result<(), StorageError> {
+        sqlx::query(
+            r#"
+            INSERT INTO vocabulary_fst (id, data) VALUES (1, $1)
+            ON CONFLICT (id) DO UPDATE SET data = EXCLUDED.data
+            "#,
+        )
+        .bind(fst_bytes)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let row = sqlx::query("SELECT data FROM vocabulary_fst WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("data")))
+    }
+
+    async fn store_correction_index(&self, index_bytes: Vec<u8>) -> Result<(), StorageError> {
+        sqlx::query(
+            r#"
+            INSERT INTO correction_index (id, data) VALUES (1, $1)
+            ON CONFLICT (id) DO UPDATE SET data = EXCLUDED.data
+            "#,
+        )
+        .bind(index_bytes)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let row = sqlx::query("SELECT data FROM correction_index WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("data")))
+    }
+
     async fn test_connection(&self) -> Result<(), StorageError> {
         sqlx::query("SELECT 1").fetch_one(&self.pool).await?;
         Ok(())
@@ -0,0 +1,14 @@
+use crate::services::progress::ProgressPublisher;
+use axum::extract::State;
+use axum::response::sse::{KeepAlive, Sse};
+use std::sync::Arc;
+
+/// Upgrades to Server-Sent Events and relays every `ProgressEvent` published
+/// onto the Redis progress channel, so a client can watch a book's indexing
+/// pipeline live instead of polling `/index/status`.
+pub async fn index_events(
+    State(progress): State<Arc<ProgressPublisher>>,
+) -> Sse<impl futures_util::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>>
+{
+    Sse::new(progress.subscribe().await).keep_alive(KeepAlive::default())
+}
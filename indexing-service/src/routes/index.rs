@@ -1,40 +1,72 @@
-use crate::models::responses::{IndexResponse, IndexStatusResponse, RebuildResponse};
+use crate::models::error::{ApiError, ApiErrorExt};
+use crate::models::responses::{
+    IndexResponse, IndexStatusResponse, RebuildResponse, WordLookupResponse, WordPosting,
+};
 use crate::models::storage::StorageBackend;
-use crate::services::indexing::process_book;
+use crate::services::indexing::{process_book, rebuild_derived_indexes};
+use crate::services::inverted_index;
+use crate::services::inverted_index::InvertedIndexReader;
+use crate::services::progress::ProgressPublisher;
+use crate::services::queue::{IndexJob, PendingBooks};
 use crate::utils::file::DATALAKE_PATH;
-use axum::{extract::Path, http::StatusCode, response::Json};
+use crate::utils::tracking_allocator;
+use axum::{extract::Path, response::Json};
 use chrono::Utc;
 use std::fs;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
 
 type Backend = Arc<dyn StorageBackend + Send + Sync>;
 
+const BYTES_PER_MB: f64 = 1_000_000.0;
+
+/// Peak-allocator-bytes threshold checked between books during a rebuild,
+/// read once per call. This is reporting, not enforcement: a book's
+/// postings are already flushed to the backend and dropped as soon as
+/// `process_book` finishes it, so there is nothing left to flush early by
+/// the time this is checked, and the tracking allocator can't safely reject
+/// an in-flight allocation from a `GlobalAlloc` impl without risking an
+/// abort. Crossing it only logs a warning so an operator can see a rebuild
+/// is running larger than expected.
+fn memory_warn_threshold_bytes() -> usize {
+    std::env::var("REBUILD_MEMORY_WARN_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(512 * 1024 * 1024)
+}
+
+/// Enqueues `book_id` for the background index worker rather than
+/// reprocessing it inline - repeat requests for the same book within the
+/// worker's flush window coalesce into a single reprocess. See
+/// `services::queue`.
 pub async fn index_book(
     Path(book_id): Path<u32>,
-    axum::extract::State(backend): axum::extract::State<Backend>,
-) -> Result<Json<IndexResponse>, StatusCode> {
-    info!("Indexing book {}", book_id);
-
-    match process_book(book_id, &backend).await {
-        Ok(()) => Ok(Json(IndexResponse {
-            book_id,
-            status: "indexed".to_string(),
-        })),
-        Err(e) => {
-            error!("Failed to index book {}: {}", book_id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+    axum::extract::State(queue): axum::extract::State<mpsc::Sender<IndexJob>>,
+) -> Result<Json<IndexResponse>, ApiError> {
+    info!("Enqueueing index update for book {}", book_id);
+
+    queue
+        .send(IndexJob { book_id })
+        .await
+        .map_err(|_| ApiError::index_backend_unavailable("index queue is no longer accepting work"))?;
+
+    Ok(Json(IndexResponse {
+        book_id,
+        status: "queued".to_string(),
+    }))
 }
 
 pub async fn rebuild_index(
     axum::extract::State(backend): axum::extract::State<Backend>,
-) -> Result<Json<RebuildResponse>, StatusCode> {
+    axum::extract::State(progress): axum::extract::State<Arc<ProgressPublisher>>,
+) -> Result<Json<RebuildResponse>, ApiError> {
     let start_time = std::time::Instant::now();
     info!("Starting index rebuild");
 
     let mut books_processed = 0;
+    let warn_threshold = memory_warn_threshold_bytes();
+    let mut threshold_warned = false;
 
     if let Ok(entries) = fs::read_dir(DATALAKE_PATH) {
         for date_entry in entries.flatten() {
@@ -61,7 +93,7 @@ pub async fn rebuild_index(
                                                 .and_then(|s| s.strip_suffix(".txt"))
                                             {
                                                 if let Ok(book_id) = book_id_str.parse::<u32>() {
-                                                    match process_book(book_id, &backend).await {
+                                                    match process_book(book_id, &backend, &progress).await {
                                                         Ok(()) => {
                                                             books_processed += 1;
                                                         }
@@ -72,6 +104,19 @@ pub async fn rebuild_index(
                                                             );
                                                         }
                                                     }
+
+                                                    if !threshold_warned
+                                                        && tracking_allocator::peak_bytes()
+                                                            > warn_threshold
+                                                    {
+                                                        threshold_warned = true;
+                                                        warn!(
+                                                            "Rebuild peak memory ({} bytes) exceeded REBUILD_MEMORY_WARN_BYTES ({} bytes) after book {}",
+                                                            tracking_allocator::peak_bytes(),
+                                                            warn_threshold,
+                                                            book_id
+                                                        );
+                                                    }
                                                 }
                                             }
                                         }
@@ -85,28 +130,67 @@ pub async fn rebuild_index(
         }
     }
 
+    // Rebuild the derived indexes once for the whole rebuild rather than once
+    // per book processed above - same reasoning as `queue::flush`.
+    if books_processed > 0 {
+        if let Err(e) = rebuild_derived_indexes(&backend).await {
+            warn!("Failed to rebuild derived indexes after full rebuild: {}", e);
+        }
+    }
+
     let elapsed = start_time.elapsed();
+    let peak_memory_mb = tracking_allocator::peak_bytes() as f64 / BYTES_PER_MB;
     info!(
-        "Index rebuild complete: {} books processed in {:?}",
-        books_processed, elapsed
+        "Index rebuild complete: {} books processed in {:?} (peak memory {:.1} MB)",
+        books_processed, elapsed, peak_memory_mb
     );
 
     Ok(Json(RebuildResponse {
         books_processed,
         elapsed_time: format!("{:.2}s", elapsed.as_secs_f64()),
+        peak_memory_mb,
     }))
 }
 
 pub async fn get_index_status(
     axum::extract::State(backend): axum::extract::State<Backend>,
+    axum::extract::State(pending): axum::extract::State<PendingBooks>,
 ) -> Json<IndexStatusResponse> {
-    let (book_count, word_count) = backend.get_stats().await.unwrap_or((0, 0));
+    let (book_count, _) = backend.get_stats().await.unwrap_or((0, 0));
 
-    let index_size_mb = (book_count * 1000 + word_count * 100) as f64 / 1_000_000.0;
+    let index_size_mb = inverted_index::on_disk_size_bytes() as f64 / BYTES_PER_MB;
+    let queue_depth = pending.lock().unwrap().len();
+    let peak_memory_mb = tracking_allocator::peak_bytes() as f64 / BYTES_PER_MB;
 
     Json(IndexStatusResponse {
         books_indexed: book_count,
         last_update: Utc::now().to_rfc3339(),
         index_size_mb,
+        queue_depth,
+        peak_memory_mb,
     })
 }
+
+/// Serves a term lookup directly from the on-disk `InvertedIndexReader`
+/// instead of `StorageBackend`, proving out the durable index as a real
+/// (if currently read-only) query path rather than write-only plumbing.
+/// Opens and parses the vocabulary fresh on every call rather than caching
+/// it in `AppState`, matching `on_disk_size_bytes`'s read-the-files-each-time
+/// approach - simple, and correct across rebuilds without an invalidation
+/// path to get wrong.
+pub async fn lookup_word(
+    Path(term): Path<String>,
+) -> Result<Json<WordLookupResponse>, ApiError> {
+    let reader = InvertedIndexReader::open().map_err(ApiError::index_read_failed)?;
+
+    let postings = reader
+        .search_word(&term)
+        .into_iter()
+        .map(|posting| WordPosting {
+            book_id: posting.book_id,
+            term_frequency: posting.term_frequency,
+        })
+        .collect();
+
+    Ok(Json(WordLookupResponse { term, postings }))
+}
@@ -0,0 +1,16 @@
+use crate::models::storage::StorageBackend;
+use crate::services::metrics::Metrics;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use std::sync::Arc;
+
+type Backend = Arc<dyn StorageBackend + Send + Sync>;
+
+pub async fn metrics_handler(
+    State(backend): State<Backend>,
+    State(metrics): State<Arc<Metrics>>,
+) -> impl IntoResponse {
+    let body = metrics.render(backend.as_ref()).await;
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
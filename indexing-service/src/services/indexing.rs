@@ -1,10 +1,30 @@
-use crate::models::storage::{BookMetadata, StorageBackend};
+use crate::models::storage::{BookMetadata, StorageBackend, StorageError};
+use crate::services::progress::{ProgressEvent, ProgressPublisher};
+use crate::services::inverted_index::rebuild_inverted_index;
+use crate::services::spellcheck::{build_correction_index, serialize as serialize_correction_index};
 use crate::utils::file::find_book_files;
-use crate::utils::text::tokenize_text;
+use crate::utils::text::{tokenize_text, ChunkedTokenizer};
+use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZstdDecoder};
+use fst::SetBuilder;
 use regex::Regex;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
 use std::sync::Arc;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
+
+#[derive(Error, Debug)]
+pub enum ProcessBookError {
+    #[error("Book {0} files not found")]
+    BookNotFound(u32),
+    #[error("I/O error reading book files: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("Failed to build vocabulary FST: {0}")]
+    Fst(#[from] fst::Error),
+}
 
 fn extract_metadata_from_header(header_content: &str, book_id: u32) -> BookMetadata {
     let title_re = Regex::new(r"(?i)title:\s*(.+)").unwrap();
@@ -46,30 +66,193 @@ fn extract_metadata_from_header(header_content: &str, book_id: u32) -> BookMetad
     }
 }
 
+/// Bytes read from the underlying decoder per streaming step. Bounds how
+/// much of the body can be in flight at once, independent of the book's
+/// total size - a multi-megabyte body never has to be materialized in full.
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Streams a book body through `ChunkedTokenizer` instead of reading it into
+/// a single `String` first, transparently stream-decompressing it if the
+/// path carries a `.zst`/`.gz`/`.br` extension. Returns the accumulated
+/// per-term frequency map and a whitespace-based word count, without ever
+/// holding more than one chunk of raw text plus the tokenizer's running
+/// frequency map in memory.
+async fn read_body_streaming(
+    path: &str,
+    language: &str,
+) -> Result<(HashMap<String, usize>, usize), ProcessBookError> {
+    let file = tokio::fs::File::open(path).await?;
+    let reader = BufReader::new(file);
+
+    let mut decoder: Box<dyn AsyncRead + Unpin + Send> = if path.ends_with(".zst") {
+        Box::new(ZstdDecoder::new(reader))
+    } else if path.ends_with(".gz") {
+        Box::new(GzipDecoder::new(reader))
+    } else if path.ends_with(".br") {
+        Box::new(BrotliDecoder::new(reader))
+    } else {
+        Box::new(reader)
+    };
+
+    let mut tokenizer = ChunkedTokenizer::new(language);
+    let mut read_buf = vec![0u8; STREAM_CHUNK_BYTES];
+    // Holds any bytes at the end of a read that look like the start of a
+    // multi-byte UTF-8 sequence the chunk boundary cut in half, so they get
+    // prefixed onto the next read instead of being decoded lossily.
+    let mut byte_carry: Vec<u8> = Vec::new();
+    let mut word_count = 0usize;
+
+    loop {
+        let n = decoder.read(&mut read_buf).await?;
+        if n == 0 {
+            break;
+        }
+        byte_carry.extend_from_slice(&read_buf[..n]);
+
+        // Definitely-invalid sequences are consumed (and decoded lossily
+        // below); only an incomplete sequence at the very end is carried.
+        let mut valid_len = 0;
+        loop {
+            match std::str::from_utf8(&byte_carry[valid_len..]) {
+                Ok(s) => {
+                    valid_len += s.len();
+                    break;
+                }
+                Err(e) => match e.error_len() {
+                    Some(bad_len) => valid_len += e.valid_up_to() + bad_len,
+                    None => {
+                        valid_len += e.valid_up_to();
+                        break;
+                    }
+                },
+            }
+        }
+        let text = String::from_utf8_lossy(&byte_carry[..valid_len]).into_owned();
+        word_count += text.split_whitespace().count();
+        tokenizer.push_chunk(&text);
+        byte_carry.drain(..valid_len);
+    }
+
+    if !byte_carry.is_empty() {
+        // Genuinely malformed trailing bytes, not just a split boundary -
+        // decode lossily rather than silently drop them.
+        let text = String::from_utf8_lossy(&byte_carry).into_owned();
+        word_count += text.split_whitespace().count();
+        tokenizer.push_chunk(&text);
+    }
+
+    Ok((tokenizer.finish(), word_count))
+}
+
+/// How many words to index between progress publishes, so a long book
+/// doesn't flood the SSE stream with one event per word.
+const PROGRESS_PUBLISH_INTERVAL: usize = 100;
+
 pub async fn process_book(
     book_id: u32,
     backend: &Arc<dyn StorageBackend + Send + Sync>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    progress: &ProgressPublisher,
+) -> Result<(), ProcessBookError> {
     let (header_path, body_path) =
-        find_book_files(book_id).ok_or(format!("Book {} files not found", book_id))?;
+        find_book_files(book_id).ok_or(ProcessBookError::BookNotFound(book_id))?;
 
     let header_content = fs::read_to_string(&header_path)?;
-    let body_content = fs::read_to_string(&body_path)?;
-
     let mut metadata = extract_metadata_from_header(&header_content, book_id);
-    let words = tokenize_text(&body_content);
-    let title_words = tokenize_text(&metadata.title);
 
-    metadata.word_count = body_content.split_whitespace().count();
-    metadata.unique_words = words.len();
+    let (body_frequencies, word_count) =
+        read_body_streaming(&body_path, &metadata.language).await?;
+    let title_words = tokenize_text(&metadata.title, &metadata.language);
 
-    let all_words: HashSet<String> = words.union(&title_words).cloned().collect();
+    metadata.word_count = word_count;
+    metadata.unique_words = body_frequencies.len();
+
+    let all_words: HashSet<String> = body_frequencies
+        .keys()
+        .cloned()
+        .chain(title_words)
+        .collect();
 
     backend.store_book_metadata(&metadata).await?;
+    publish_progress(progress, book_id, 0, "metadata_stored").await;
 
+    let mut words_indexed = 0;
     for word in &all_words {
-        backend.add_word_to_index(word, book_id).await?;
+        // Title-only words (not present in the body) still get indexed, just
+        // with a nominal term frequency of 1 since they have no body count.
+        let term_frequency = body_frequencies.get(word).copied().unwrap_or(1);
+        backend
+            .add_word_to_index(word, book_id, term_frequency)
+            .await?;
+
+        words_indexed += 1;
+        if words_indexed % PROGRESS_PUBLISH_INTERVAL == 0 {
+            publish_progress(progress, book_id, words_indexed, "indexing").await;
+        }
+    }
+
+    publish_progress(progress, book_id, words_indexed, "indexed").await;
+
+    Ok(())
+}
+
+/// Rebuilds the vocabulary FST, SymSpell correction index, and on-disk
+/// inverted index from whatever is currently in the backend. Each is a
+/// full-vocabulary rebuild, not an incremental update, so callers that
+/// process a batch of books should call this once after the batch rather
+/// than once per book - see `services::queue::flush` and
+/// `routes::index::rebuild_index`.
+pub async fn rebuild_derived_indexes(
+    backend: &Arc<dyn StorageBackend + Send + Sync>,
+) -> Result<(), ProcessBookError> {
+    rebuild_vocabulary_fst(backend).await?;
+    rebuild_correction_index(backend).await?;
+    rebuild_inverted_index(backend).await?;
+    Ok(())
+}
+
+/// Best-effort: a subscriber being unreachable is an observability gap, not
+/// a reason to fail the indexing pipeline itself.
+async fn publish_progress(progress: &ProgressPublisher, book_id: u32, words_indexed: usize, status: &str) {
+    let event = ProgressEvent {
+        book_id,
+        words_indexed,
+        status: status.to_string(),
+    };
+    if let Err(e) = progress.publish(&event).await {
+        tracing::warn!("Failed to publish progress event for book {}: {}", book_id, e);
     }
+}
 
+/// Rebuilds the fuzzy-lookup FST from the full indexed vocabulary and persists
+/// it via the backend, so `search-service` can intersect Levenshtein automata
+/// against it without ever touching raw postings.
+async fn rebuild_vocabulary_fst(
+    backend: &Arc<dyn StorageBackend + Send + Sync>,
+) -> Result<(), ProcessBookError> {
+    let mut terms = backend.get_all_words().await?;
+    terms.sort();
+    terms.dedup();
+
+    let mut builder = SetBuilder::memory();
+    for term in &terms {
+        builder.insert(term)?;
+    }
+    let fst_bytes = builder.into_inner()?;
+
+    backend.store_vocabulary_fst(fst_bytes).await?;
+
+    Ok(())
+}
+
+/// Rebuilds the SymSpell-style deletion dictionary from the full indexed
+/// vocabulary and persists it via the backend, so `search-service` can
+/// correct a misspelled query term without ever touching raw postings.
+async fn rebuild_correction_index(
+    backend: &Arc<dyn StorageBackend + Send + Sync>,
+) -> Result<(), ProcessBookError> {
+    let terms = backend.get_all_words().await?;
+    let index = build_correction_index(&terms);
+    let bytes = serialize_correction_index(&index).map_err(StorageError::from)?;
+    backend.store_correction_index(bytes).await?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,200 @@
+use crate::models::storage::StorageBackend;
+use crate::services::indexing::ProcessBookError;
+use crate::utils::file::DATALAKE_PATH;
+use crate::utils::varint::{decode_varint, encode_varint};
+use memmap2::{Mmap, MmapOptions};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn index_dir() -> PathBuf {
+    PathBuf::from(DATALAKE_PATH).join("index")
+}
+
+pub fn vocabulary_path() -> PathBuf {
+    index_dir().join("vocabulary.bin")
+}
+
+pub fn postings_path() -> PathBuf {
+    index_dir().join("postings.bin")
+}
+
+/// Rebuilds the on-disk inverted index - a sorted vocabulary file mapping
+/// each term to its offset and byte length in a postings file, and a
+/// postings file holding each term's `(book_id, tf)` pairs delta-gap-encoded
+/// and varint-compressed - from the full current vocabulary. Regenerated
+/// after every batch for the same reason `rebuild_vocabulary_fst` and
+/// `rebuild_correction_index` are: none of these derived structures has a
+/// per-term update path of its own, so each is always rebuilt fresh from the
+/// backend's postings rather than patched in place.
+///
+/// Read back by `InvertedIndexReader`, which mmaps `postings.bin` and serves
+/// `GET /index/word/:term` directly from this format instead of the
+/// `StorageBackend` - see `routes::index::lookup_word`.
+pub async fn rebuild_inverted_index(
+    backend: &Arc<dyn StorageBackend + Send + Sync>,
+) -> Result<(), ProcessBookError> {
+    let mut terms = backend.get_all_words().await?;
+    terms.sort();
+    terms.dedup();
+
+    let mut vocabulary = Vec::new();
+    let mut postings = Vec::new();
+
+    for term in &terms {
+        let mut book_ids: Vec<u32> = backend.search_word(term).await?.into_iter().collect();
+        book_ids.sort_unstable();
+
+        let offset = postings.len() as u64;
+        encode_varint(book_ids.len() as u64, &mut postings);
+
+        let mut previous = 0u32;
+        for book_id in &book_ids {
+            let gap = book_id - previous;
+            previous = *book_id;
+            let tf = backend.get_term_frequency(term, *book_id).await?;
+            encode_varint(gap as u64, &mut postings);
+            encode_varint(tf as u64, &mut postings);
+        }
+        let length = postings.len() as u64 - offset;
+
+        encode_varint(term.len() as u64, &mut vocabulary);
+        vocabulary.extend_from_slice(term.as_bytes());
+        encode_varint(offset, &mut vocabulary);
+        encode_varint(length, &mut vocabulary);
+    }
+
+    tokio::fs::create_dir_all(index_dir()).await?;
+    write_atomically(&vocabulary_path(), &vocabulary).await?;
+    write_atomically(&postings_path(), &postings).await?;
+
+    Ok(())
+}
+
+/// Writes via a temp file plus rename so a crash mid-write never leaves a
+/// truncated index file on disk.
+async fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, bytes).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+/// Combined byte size of the vocabulary and postings files on disk, so
+/// `get_index_status` can report real usage instead of an estimate. Reads 0
+/// for either file that hasn't been written yet (e.g. before the first
+/// book is indexed).
+pub fn on_disk_size_bytes() -> u64 {
+    [vocabulary_path(), postings_path()]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// One `(book_id, term_frequency)` pair decoded out of a term's postings run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Posting {
+    pub book_id: u32,
+    pub term_frequency: u32,
+}
+
+/// Read side of the format `rebuild_inverted_index` writes. The vocabulary
+/// (one entry per term, at most a few bytes each) is small enough to parse
+/// fully into memory up front; the postings file is mmapped instead of
+/// loaded, since it can grow much larger than the terms that index into it
+/// and a lookup only ever touches one term's byte range.
+pub struct InvertedIndexReader {
+    vocabulary: Vec<(String, u64, u64)>,
+    postings: Option<Mmap>,
+}
+
+impl InvertedIndexReader {
+    /// Opens the current on-disk index. Returns a reader with an empty
+    /// vocabulary (every lookup misses) if `rebuild_inverted_index` hasn't
+    /// written either file yet, rather than an error - that's the normal
+    /// state before the first book is indexed.
+    pub fn open() -> std::io::Result<Self> {
+        let vocabulary = match std::fs::read(vocabulary_path()) {
+            Ok(bytes) => parse_vocabulary(&bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let postings = match File::open(postings_path()) {
+            Ok(file) => Some(unsafe { MmapOptions::new().map(&file)? }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            vocabulary,
+            postings,
+        })
+    }
+
+    /// Looks up `term` in the in-memory vocabulary and, on a hit, decodes its
+    /// postings run out of the mmapped postings file. Empty if the term
+    /// isn't in the index or the postings file is missing.
+    pub fn search_word(&self, term: &str) -> Vec<Posting> {
+        let Some(postings) = &self.postings else {
+            return Vec::new();
+        };
+        let Ok(index) = self
+            .vocabulary
+            .binary_search_by(|(candidate, _, _)| candidate.as_str().cmp(term))
+        else {
+            return Vec::new();
+        };
+
+        let (_, offset, length) = &self.vocabulary[index];
+        let run = &postings[*offset as usize..(*offset + *length) as usize];
+
+        let mut cursor = 0;
+        let (count, consumed) = decode_varint(run);
+        cursor += consumed;
+
+        let mut result = Vec::with_capacity(count as usize);
+        let mut book_id = 0u32;
+        for _ in 0..count {
+            let (gap, consumed) = decode_varint(&run[cursor..]);
+            cursor += consumed;
+            let (tf, consumed) = decode_varint(&run[cursor..]);
+            cursor += consumed;
+
+            book_id += gap as u32;
+            result.push(Posting {
+                book_id,
+                term_frequency: tf as u32,
+            });
+        }
+
+        result
+    }
+}
+
+/// Parses the flat `(term_len, term_bytes, offset, length)*` vocabulary
+/// format into a lookup table. Entries come out in the same order
+/// `rebuild_inverted_index` wrote them in - sorted by term - so the result
+/// is ready for `binary_search_by` without an extra sort.
+fn parse_vocabulary(bytes: &[u8]) -> Vec<(String, u64, u64)> {
+    let mut entries = Vec::new();
+    let mut cursor = 0;
+
+    while cursor < bytes.len() {
+        let (term_len, consumed) = decode_varint(&bytes[cursor..]);
+        cursor += consumed;
+
+        let term = String::from_utf8_lossy(&bytes[cursor..cursor + term_len as usize]).into_owned();
+        cursor += term_len as usize;
+
+        let (offset, consumed) = decode_varint(&bytes[cursor..]);
+        cursor += consumed;
+        let (length, consumed) = decode_varint(&bytes[cursor..]);
+        cursor += consumed;
+
+        entries.push((term, offset, length));
+    }
+
+    entries
+}
@@ -0,0 +1,217 @@
+use crate::models::storage::{BookMetadata, StorageBackend, StorageError};
+use async_trait::async_trait;
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Holds the process's Prometheus registry plus the counters/histograms that
+/// don't belong to any single storage backend. Index size itself isn't kept
+/// here as a gauge - `render` reads it live from `StorageBackend::get_stats`
+/// on every scrape instead, so it can never drift from the real index.
+pub struct Metrics {
+    registry: Registry,
+    index_book_total: IntCounterVec,
+    storage_op_duration: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let index_book_total = IntCounterVec::new(
+            Opts::new(
+                "index_book_requests_total",
+                "Count of books processed by the index queue, by outcome",
+            ),
+            &["result"],
+        )
+        .expect("valid index_book_requests_total metric");
+        registry
+            .register(Box::new(index_book_total.clone()))
+            .expect("index_book_requests_total registers once");
+
+        let storage_op_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "storage_operation_duration_seconds",
+                "Latency of StorageBackend operations",
+            ),
+            &["operation"],
+        )
+        .expect("valid storage_operation_duration_seconds metric");
+        registry
+            .register(Box::new(storage_op_duration.clone()))
+            .expect("storage_operation_duration_seconds registers once");
+
+        Self {
+            registry,
+            index_book_total,
+            storage_op_duration,
+        }
+    }
+
+    pub fn record_index_book(&self, success: bool) {
+        let result = if success { "success" } else { "failure" };
+        self.index_book_total.with_label_values(&[result]).inc();
+    }
+
+    fn observe_storage_op(&self, operation: &str, start: Instant) {
+        self.storage_op_duration
+            .with_label_values(&[operation])
+            .observe(start.elapsed().as_secs_f64());
+    }
+
+    /// Renders the registered counters/histograms as Prometheus text format,
+    /// plus a pair of gauges for current index size pulled live from `backend`.
+    pub async fn render(&self, backend: &(dyn StorageBackend + Send + Sync)) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("Prometheus text encoding cannot fail for valid metric families");
+        let mut out = String::from_utf8(buffer).expect("Prometheus text encoder emits UTF-8");
+
+        if let Ok((books_indexed, unique_words)) = backend.get_stats().await {
+            out.push_str("# HELP indexing_books_indexed Total number of books currently in the index.\n");
+            out.push_str("# TYPE indexing_books_indexed gauge\n");
+            out.push_str(&format!("indexing_books_indexed {}\n", books_indexed));
+            out.push_str("# HELP indexing_unique_words Total number of distinct indexed terms.\n");
+            out.push_str("# TYPE indexing_unique_words gauge\n");
+            out.push_str(&format!("indexing_unique_words {}\n", unique_words));
+        }
+
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wraps a `StorageBackend` to time every call into `storage_op_duration`,
+/// so Redis and Postgres feed the same histogram regardless of which one is
+/// configured - the route layer and `process_book` never know the difference.
+pub struct InstrumentedBackend {
+    inner: Arc<dyn StorageBackend + Send + Sync>,
+    metrics: Arc<Metrics>,
+}
+
+impl InstrumentedBackend {
+    pub fn new(inner: Arc<dyn StorageBackend + Send + Sync>, metrics: Arc<Metrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for InstrumentedBackend {
+    async fn store_book_metadata(&self, metadata: &BookMetadata) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.store_book_metadata(metadata).await;
+        self.metrics.observe_storage_op("store_book_metadata", start);
+        result
+    }
+
+    async fn get_book_metadata(&self, book_id: u32) -> Result<Option<BookMetadata>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_book_metadata(book_id).await;
+        self.metrics.observe_storage_op("get_book_metadata", start);
+        result
+    }
+
+    async fn is_book_indexed(&self, book_id: u32) -> Result<bool, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.is_book_indexed(book_id).await;
+        self.metrics.observe_storage_op("is_book_indexed", start);
+        result
+    }
+
+    async fn get_indexed_books(&self) -> Result<HashSet<u32>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_indexed_books().await;
+        self.metrics.observe_storage_op("get_indexed_books", start);
+        result
+    }
+
+    async fn add_word_to_index(
+        &self,
+        word: &str,
+        book_id: u32,
+        term_frequency: usize,
+    ) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.add_word_to_index(word, book_id, term_frequency).await;
+        self.metrics.observe_storage_op("add_word_to_index", start);
+        result
+    }
+
+    async fn search_word(&self, word: &str) -> Result<HashSet<u32>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.search_word(word).await;
+        self.metrics.observe_storage_op("search_word", start);
+        result
+    }
+
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_term_frequency(word, book_id).await;
+        self.metrics.observe_storage_op("get_term_frequency", start);
+        result
+    }
+
+    async fn get_stats(&self) -> Result<(usize, usize), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_stats().await;
+        self.metrics.observe_storage_op("get_stats", start);
+        result
+    }
+
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_avg_doc_length().await;
+        self.metrics.observe_storage_op("get_avg_doc_length", start);
+        result
+    }
+
+    async fn get_all_words(&self) -> Result<Vec<String>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_all_words().await;
+        self.metrics.observe_storage_op("get_all_words", start);
+        result
+    }
+
+    async fn store_vocabulary_fst(&self, fst_bytes: Vec<u8>) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.store_vocabulary_fst(fst_bytes).await;
+        self.metrics.observe_storage_op("store_vocabulary_fst", start);
+        result
+    }
+
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_vocabulary_fst().await;
+        self.metrics.observe_storage_op("get_vocabulary_fst", start);
+        result
+    }
+
+    async fn store_correction_index(&self, index_bytes: Vec<u8>) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.store_correction_index(index_bytes).await;
+        self.metrics.observe_storage_op("store_correction_index", start);
+        result
+    }
+
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_correction_index().await;
+        self.metrics.observe_storage_op("get_correction_index", start);
+        result
+    }
+
+    async fn test_connection(&self) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.test_connection().await;
+        self.metrics.observe_storage_op("test_connection", start);
+        result
+    }
+}
@@ -0,0 +1,77 @@
+use crate::models::storage::StorageError;
+use axum::response::sse::Event;
+use deadpool_redis::{Config as RedisConfig, Pool as RedisPool, Runtime};
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+pub const PROGRESS_CHANNEL: &str = "index:progress";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub book_id: u32,
+    pub words_indexed: usize,
+    pub status: String,
+}
+
+/// Publishes indexing progress onto a Redis pub/sub channel and lets SSE
+/// clients subscribe to it, independent of whichever `StorageBackend` is
+/// configured - pub/sub has no Postgres equivalent, so this always talks to
+/// Redis directly rather than going through the storage trait.
+pub struct ProgressPublisher {
+    client: redis::Client,
+    pool: RedisPool,
+}
+
+impl ProgressPublisher {
+    pub fn new(redis_url: &str) -> Result<Self, StorageError> {
+        let client = redis::Client::open(redis_url)?;
+        let pool = RedisConfig::from_url(redis_url)
+            .create_pool(Some(Runtime::Tokio1))
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(Self { client, pool })
+    }
+
+    /// `publish_progress` fires multiple times per book (metadata stored,
+    /// every `PROGRESS_PUBLISH_INTERVAL` words, and the final status), so
+    /// this goes through the same pooled-connection pattern as
+    /// `RedisBackend` instead of dialing a fresh connection on every call.
+    pub async fn publish(&self, event: &ProgressEvent) -> Result<(), StorageError> {
+        let payload = serde_json::to_string(event)?;
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        redis::AsyncCommands::publish::<_, _, ()>(&mut conn, PROGRESS_CHANNEL, payload).await?;
+        Ok(())
+    }
+
+    /// Opens a dedicated (non-pooled) subscriber connection and relays each
+    /// published `ProgressEvent` as an SSE `Event` until the client
+    /// disconnects or the connection drops.
+    pub async fn subscribe(&self) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
+        let connection = self.client.get_async_connection().await;
+
+        async_stream::stream! {
+            let mut pubsub = match connection {
+                Ok(conn) => conn.into_pubsub(),
+                Err(e) => {
+                    tracing::error!("Failed to open progress subscriber connection: {}", e);
+                    return;
+                }
+            };
+
+            if let Err(e) = pubsub.subscribe(PROGRESS_CHANNEL).await {
+                tracing::error!("Failed to subscribe to {}: {}", PROGRESS_CHANNEL, e);
+                return;
+            }
+
+            let mut messages = pubsub.on_message();
+            while let Some(msg) = messages.next().await {
+                if let Ok(payload) = msg.get_payload::<String>() {
+                    yield Ok(Event::default().data(payload));
+                }
+            }
+        }
+    }
+}
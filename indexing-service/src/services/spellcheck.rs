@@ -0,0 +1,59 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// How many character deletions a vocabulary term's variants are
+/// precomputed for, matching the edit-distance budget `search-service`
+/// corrects against.
+const MAX_EDIT_DISTANCE: usize = 2;
+
+/// SymSpell-style deletion-neighborhood dictionary: maps every string
+/// obtainable by deleting up to `MAX_EDIT_DISTANCE` characters from a
+/// vocabulary term to the terms that produced it. A misspelled query term
+/// is corrected by generating its own delete-variants and looking up the
+/// union of originating terms, instead of scanning the full vocabulary.
+#[derive(Debug, Default, Serialize)]
+pub struct CorrectionIndex {
+    pub deletions: HashMap<String, Vec<String>>,
+}
+
+fn delete_variants(term: &str, max_deletions: usize) -> HashSet<String> {
+    let mut variants = HashSet::new();
+    variants.insert(term.to_string());
+
+    let mut frontier = vec![term.to_string()];
+    for _ in 0..max_deletions {
+        let mut next = Vec::new();
+        for word in &frontier {
+            let chars: Vec<char> = word.chars().collect();
+            for i in 0..chars.len() {
+                let variant: String = chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| *idx != i)
+                    .map(|(_, c)| *c)
+                    .collect();
+                if variants.insert(variant.clone()) {
+                    next.push(variant);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    variants
+}
+
+/// Builds the deletion dictionary from the full indexed vocabulary.
+pub fn build_correction_index(vocabulary: &[String]) -> CorrectionIndex {
+    let mut deletions: HashMap<String, Vec<String>> = HashMap::new();
+    for term in vocabulary {
+        for variant in delete_variants(term, MAX_EDIT_DISTANCE) {
+            deletions.entry(variant).or_default().push(term.clone());
+        }
+    }
+    CorrectionIndex { deletions }
+}
+
+pub fn serialize(index: &CorrectionIndex) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(index)
+}
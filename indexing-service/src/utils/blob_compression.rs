@@ -0,0 +1,90 @@
+use std::io::{Read, Write};
+
+/// Codec for compressing small in-memory blobs (serialized metadata JSON)
+/// before they're stored in Redis, independent of the `CompressionCodec`
+/// used for book bodies on the datalake. These blobs are a few hundred
+/// bytes, so synchronous compression over an in-memory buffer is simpler
+/// than routing them through the async streaming codecs used for bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobCodec {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl BlobCodec {
+    pub fn from_env() -> Self {
+        match std::env::var("STORAGE_COMPRESSION").as_deref() {
+            Ok("gzip") => BlobCodec::Gzip,
+            Ok("zstd") => BlobCodec::Zstd,
+            _ => BlobCodec::None,
+        }
+    }
+
+    /// Tag prefixed onto compressed output so `decompress` always knows
+    /// which codec produced a given blob, even after `STORAGE_COMPRESSION`
+    /// changes - old blobs keep decoding correctly.
+    fn tag(self) -> u8 {
+        match self {
+            BlobCodec::None => 0,
+            BlobCodec::Gzip => 1,
+            BlobCodec::Zstd => 2,
+        }
+    }
+}
+
+pub fn compress(codec: BlobCodec, data: &[u8]) -> Vec<u8> {
+    let mut out = vec![codec.tag()];
+    match codec {
+        BlobCodec::None => out.extend_from_slice(data),
+        BlobCodec::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder
+                .write_all(data)
+                .expect("in-memory gzip encoding cannot fail");
+            out.extend(
+                encoder
+                    .finish()
+                    .expect("in-memory gzip encoding cannot fail"),
+            );
+        }
+        BlobCodec::Zstd => {
+            out.extend(zstd::encode_all(data, 0).expect("in-memory zstd encoding cannot fail"));
+        }
+    }
+    out
+}
+
+/// Decodes a blob produced by `compress`, regardless of the codec currently
+/// configured via `STORAGE_COMPRESSION` - the leading tag byte is
+/// authoritative.
+///
+/// Every blob this decodes is book metadata JSON, so a blob written before
+/// the tag byte existed is indistinguishable from a tagged one except that
+/// its first byte is JSON (`{`) rather than a codec tag - reading it as a
+/// tag would silently strip that byte and corrupt the JSON. Parsing the
+/// whole buffer as JSON first catches that case and returns it untouched;
+/// anything that isn't valid JSON on its own falls through to the tagged
+/// format.
+pub fn decompress(data: &[u8]) -> Vec<u8> {
+    if serde_json::from_slice::<serde::de::IgnoredAny>(data).is_ok() {
+        return data.to_vec();
+    }
+
+    let Some((&tag, body)) = data.split_first() else {
+        return Vec::new();
+    };
+    match tag {
+        1 => {
+            let mut decoder = flate2::read::GzDecoder::new(body);
+            let mut out = Vec::new();
+            decoder
+                .read_to_end(&mut out)
+                .expect("stored gzip blob is well-formed");
+            out
+        }
+        2 => zstd::decode_all(body).expect("stored zstd blob is well-formed"),
+        _ => body.to_vec(),
+    }
+}
@@ -19,14 +19,23 @@ pub fn find_book_files(book_id: u32) -> Option<(String, String)> {
                         {
                             let header_path =
                                 subdir_entry.path().join(format!("header_{}.txt", book_id));
-                            let body_path =
-                                subdir_entry.path().join(format!("body_{}.txt", book_id));
+                            if !header_path.exists() {
+                                continue;
+                            }
 
-                            if header_path.exists() && body_path.exists() {
-                                return Some((
-                                    header_path.to_string_lossy().to_string(),
-                                    body_path.to_string_lossy().to_string(),
-                                ));
+                            // Prefer an existing plaintext body over any
+                            // compressed variant, for books ingested before
+                            // datalake compression was enabled.
+                            for ext in ["txt", "txt.zst", "txt.gz", "txt.br"] {
+                                let body_path = subdir_entry
+                                    .path()
+                                    .join(format!("body_{}.{}", book_id, ext));
+                                if body_path.exists() {
+                                    return Some((
+                                        header_path.to_string_lossy().to_string(),
+                                        body_path.to_string_lossy().to_string(),
+                                    ));
+                                }
                             }
                         }
                     }
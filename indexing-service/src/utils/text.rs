@@ -1,10 +1,64 @@
-use regex::Regex;
-use std::collections::HashSet;
-
-pub fn tokenize_text(text: &str) -> HashSet<String> {
-    let re = Regex::new(r"\b[a-zA-Z]+\b").unwrap();
-    re.find_iter(&text.to_lowercase())
-        .map(|m| m.as_str().to_string())
-        .filter(|word| word.len() > 2)
-        .collect()
-}
\ No newline at end of file
+use std::collections::HashMap;
+
+pub use common_tokenize::{tokenize_text, tokenize_with_options, tokenize_with_positions};
+
+/// Like `tokenize_text`, but keeps the raw per-term occurrence count so
+/// callers can feed term frequencies into ranking (e.g. BM25).
+pub fn tokenize_with_frequencies(text: &str, language: &str) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for (term, _position) in tokenize_with_positions(text, language) {
+        *frequencies.entry(term).or_insert(0) += 1;
+    }
+    frequencies
+}
+
+/// Accumulates term frequencies across a body fed in as a series of chunks,
+/// so a caller never has to hold the full body text in memory at once -
+/// only this map (vocabulary-sized) plus whatever the current chunk costs.
+/// Carries a trailing run of letters across a `push_chunk` boundary rather
+/// than tokenizing it early, so a word split across two chunks still
+/// collapses to one term.
+pub struct ChunkedTokenizer {
+    language: String,
+    carry: String,
+    frequencies: HashMap<String, usize>,
+}
+
+impl ChunkedTokenizer {
+    pub fn new(language: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            carry: String::new(),
+            frequencies: HashMap::new(),
+        }
+    }
+
+    /// Feeds in the next slice of body text. Everything up to the last
+    /// non-letter character is tokenized immediately; any trailing run of
+    /// letters is held back in `carry` in case the word continues in the
+    /// next chunk.
+    pub fn push_chunk(&mut self, chunk: &str) {
+        self.carry.push_str(chunk);
+        let split_at = self
+            .carry
+            .rfind(|c: char| !c.is_alphabetic())
+            .map(|i| i + self.carry[i..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(0);
+        let ready: String = self.carry.drain(..split_at).collect();
+        self.tokenize_into(&ready);
+    }
+
+    /// Tokenizes whatever letters are left in `carry` and returns the
+    /// accumulated term-frequency map for the whole body.
+    pub fn finish(mut self) -> HashMap<String, usize> {
+        let tail = std::mem::take(&mut self.carry);
+        self.tokenize_into(&tail);
+        self.frequencies
+    }
+
+    fn tokenize_into(&mut self, text: &str) {
+        for (term, _position) in tokenize_with_positions(text, &self.language) {
+            *self.frequencies.entry(term).or_insert(0) += 1;
+        }
+    }
+}
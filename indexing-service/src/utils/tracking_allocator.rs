@@ -0,0 +1,36 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator to track live and peak allocated bytes, so
+/// `/index/rebuild` can report the peak memory a rebuild actually used
+/// instead of an estimate based on input size. Only the peak is exposed
+/// (see `peak_bytes`) - per-book streaming already bounds live memory by
+/// construction (each book's body and frequency map are dropped before the
+/// next book starts), so there's nothing for a live-bytes check to enforce.
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+/// Highest number of bytes the allocator has had outstanding at once,
+/// process-wide. Monotonic - there's no API to reset it, since a rebuild's
+/// peak is only meaningful relative to the process's overall ceiling.
+pub fn peak_bytes() -> usize {
+    PEAK_BYTES.load(Ordering::Relaxed)
+}
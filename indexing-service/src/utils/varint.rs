@@ -0,0 +1,32 @@
+/// LEB128 variable-byte encoding: 7 value bits per byte, high bit set on
+/// every byte but the last. Used by `services::inverted_index` to keep the
+/// on-disk postings file compact - most book ids and term frequencies fit in
+/// one or two bytes instead of a fixed 4 or 8.
+pub fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Decodes one varint starting at `buf[0]`, returning the value and the
+/// number of bytes it consumed. Used by
+/// `services::inverted_index::InvertedIndexReader` to read back what
+/// `encode_varint` wrote.
+pub fn decode_varint(buf: &[u8]) -> (u64, usize) {
+    let mut value = 0u64;
+    let mut shift = 0;
+    for (i, &byte) in buf.iter().enumerate() {
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return (value, i + 1);
+        }
+        shift += 7;
+    }
+    (value, buf.len())
+}
@@ -2,23 +2,37 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use std::collections::HashSet;
-use std::sync::{Arc, Mutex};
+use models::task::{TaskIdGenerator, TaskRegistry};
+use services::queue::{run_worker, IngestJob};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
+use tower_http::decompression::RequestDecompressionLayer;
 use tower_http::trace::TraceLayer;
 use tracing::info;
 
-type DownloadedBooks = Arc<Mutex<HashSet<u32>>>;
-
 mod models;
 mod routes;
 mod services;
 mod utils;
 
+use common_middleware::RequestIdLayer;
 use routes::{
     health::health_check,
-    ingest::{check_status, ingest_book, list_books},
+    ingest::{check_status, get_task_status, ingest_book, list_books},
 };
+use std::net::SocketAddr;
+
+/// Shared state for the ingestion API: a channel into the background
+/// download worker, plus the registry the worker reports progress into.
+#[derive(Clone)]
+pub struct AppState {
+    pub queue: mpsc::Sender<IngestJob>,
+    pub registry: TaskRegistry,
+    pub task_ids: Arc<TaskIdGenerator>,
+}
 
 #[tokio::main]
 async fn main() {
@@ -26,16 +40,29 @@ async fn main() {
         .with_env_filter("ingestion_service=info,tower_http=info")
         .init();
 
-    let downloaded_books: DownloadedBooks = Arc::new(Mutex::new(HashSet::new()));
+    let registry: TaskRegistry = Arc::new(std::sync::Mutex::new(HashMap::new()));
+    let (queue_tx, queue_rx) = mpsc::channel::<IngestJob>(100);
+
+    tokio::spawn(run_worker(queue_rx, registry.clone()));
+
+    let state = AppState {
+        queue: queue_tx,
+        registry,
+        task_ids: Arc::new(TaskIdGenerator::default()),
+    };
 
     let app = Router::new()
         .route("/status", get(health_check))
         .route("/ingest/:book_id", post(ingest_book))
         .route("/ingest/status/:book_id", get(check_status))
+        .route("/ingest/task/:task_id", get(get_task_status))
         .route("/ingest/list", get(list_books))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
-        .with_state(downloaded_books);
+        .layer(RequestIdLayer)
+        .layer(CompressionLayer::new())
+        .layer(RequestDecompressionLayer::new())
+        .with_state(state);
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "7001".to_string());
     let addr = format!("0.0.0.0:{}", port);
@@ -43,5 +70,10 @@ async fn main() {
     info!("Ingestion service starting on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
@@ -0,0 +1,50 @@
+use axum::http::StatusCode;
+pub use common_error::ApiError;
+
+/// This service's error constructors, added via an extension trait since
+/// `ApiError` itself now lives in `common_error` and inherent impls can only
+/// be added from the crate that defines the type.
+pub trait ApiErrorExt {
+    fn book_not_found(book_id: u32) -> Self;
+    fn download_failed(source: impl std::fmt::Display) -> Self;
+    fn task_not_found(task_id: u64) -> Self;
+    fn queue_unavailable(source: impl std::fmt::Display) -> Self;
+}
+
+impl ApiErrorExt for ApiError {
+    fn book_not_found(book_id: u32) -> Self {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            "book_not_found",
+            "not_found",
+            format!("Book {} was not found on Project Gutenberg", book_id),
+        )
+    }
+
+    fn download_failed(source: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "download_failed",
+            "upstream_error",
+            format!("Failed to download book: {}", source),
+        )
+    }
+
+    fn task_not_found(task_id: u64) -> Self {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            "task_not_found",
+            "not_found",
+            format!("No ingest task with id {} was found", task_id),
+        )
+    }
+
+    fn queue_unavailable(source: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "queue_unavailable",
+            "backend_error",
+            format!("Ingest task queue is unavailable: {}", source),
+        )
+    }
+}
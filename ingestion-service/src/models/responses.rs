@@ -13,6 +13,23 @@ pub struct IngestResponse {
     pub path: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnqueueResponse {
+    pub task_id: String,
+    pub book_id: u32,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskStatusResponse {
+    pub task_id: String,
+    pub book_id: u32,
+    pub status: String,
+    pub path: Option<String>,
+    pub error: Option<String>,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StatusResponse {
     pub book_id: u32,
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A background download task tracked from enqueue through indexing.
+/// `status` is a plain string discriminant (`"enqueued"`, `"downloading"`,
+/// `"indexing"`, `"succeeded"`, `"failed"`) rather than a serialized enum, so
+/// the JSON shape stays flat and stable for API clients. `"succeeded"` is
+/// only reached after indexing-service confirms the book is searchable, not
+/// as soon as the download lands on disk - see `services::queue::process_job`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Task {
+    pub task_id: u64,
+    pub book_id: u32,
+    pub status: String,
+    pub path: Option<String>,
+    pub error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Task {
+    pub fn enqueued(task_id: u64, book_id: u32) -> Self {
+        Self {
+            task_id,
+            book_id,
+            status: "enqueued".to_string(),
+            path: None,
+            error: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+pub type TaskRegistry = Arc<Mutex<HashMap<u64, Task>>>;
+
+/// Monotonic task-id generator shared across requests. Plain atomic counter,
+/// since the service has no existing need for globally unique (UUID-style)
+/// identifiers.
+#[derive(Debug, Default)]
+pub struct TaskIdGenerator(AtomicU64);
+
+impl TaskIdGenerator {
+    pub fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
@@ -1,40 +1,70 @@
-use crate::models::responses::{IngestResponse, ListResponse, StatusResponse};
-use crate::services::download::download_book;
-use crate::utils::file::{create_datalake_path, DATALAKE_PATH};
-use axum::{extract::Path, http::StatusCode, response::Json};
-use std::collections::HashSet;
+use crate::models::error::{ApiError, ApiErrorExt};
+use crate::models::responses::{EnqueueResponse, ListResponse, StatusResponse, TaskStatusResponse};
+use crate::models::task::Task;
+use crate::services::queue::IngestJob;
+use crate::utils::file::{create_datalake_path, existing_body_path, DATALAKE_PATH};
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
 use std::fs;
-use std::sync::{Arc, Mutex};
-use tracing::error;
-type DownloadedBooks = Arc<Mutex<HashSet<u32>>>;
 
+/// Enqueues a download job and returns immediately; poll
+/// `GET /ingest/task/:id` for progress instead of waiting on the download.
 pub async fn ingest_book(
     Path(book_id): Path<u32>,
-    downloaded_books: axum::extract::State<DownloadedBooks>,
-) -> Result<Json<IngestResponse>, StatusCode> {
-    match download_book(book_id).await {
-        Ok(path) => {
-            downloaded_books.lock().unwrap().insert(book_id);
-            Ok(Json(IngestResponse {
-                book_id,
-                status: "downloaded".to_string(),
-                path,
-            }))
-        }
-        Err(e) => {
-            error!("Failed to download book {}: {}", book_id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<EnqueueResponse>), ApiError> {
+    let task_id = state.task_ids.next();
+    state
+        .registry
+        .lock()
+        .unwrap()
+        .insert(task_id, Task::enqueued(task_id, book_id));
+
+    state
+        .queue
+        .send(IngestJob { task_id, book_id })
+        .await
+        .map_err(ApiError::queue_unavailable)?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(EnqueueResponse {
+            task_id: task_id.to_string(),
+            book_id,
+            status: "enqueued".to_string(),
+        }),
+    ))
+}
+
+pub async fn get_task_status(
+    Path(task_id): Path<u64>,
+    State(state): State<AppState>,
+) -> Result<Json<TaskStatusResponse>, ApiError> {
+    let registry = state.registry.lock().unwrap();
+    let task = registry
+        .get(&task_id)
+        .ok_or_else(|| ApiError::task_not_found(task_id))?;
+
+    Ok(Json(TaskStatusResponse {
+        task_id: task.task_id.to_string(),
+        book_id: task.book_id,
+        status: task.status.clone(),
+        path: task.path.clone(),
+        error: task.error.clone(),
+        updated_at: task.updated_at.to_rfc3339(),
+    }))
 }
 
 pub async fn check_status(Path(book_id): Path<u32>) -> Json<StatusResponse> {
     let datalake_path = create_datalake_path(book_id);
     let header_path = format!("{}/header_{}.txt", datalake_path, book_id);
-    let body_path = format!("{}/body_{}.txt", datalake_path, book_id);
 
     let status = if std::path::Path::new(&header_path).exists()
-        && std::path::Path::new(&body_path).exists()
+        && existing_body_path(&datalake_path, book_id).is_some()
     {
         "available"
     } else {
@@ -1,10 +1,62 @@
-use crate::utils::file::{create_datalake_path, header_body_split};
+use crate::utils::file::{create_datalake_path, existing_body_path, header_body_split, CompressionCodec};
+use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::write::{BrotliEncoder, GzipEncoder, ZstdEncoder};
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
 use std::fs;
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
 use tracing::info;
 
-pub async fn download_book(
-    book_id: u32,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error("Book {0} not found on Project Gutenberg")]
+    NotFound(u32),
+    #[error("Gutenberg returned {1} for book {0}")]
+    UpstreamStatus(u32, reqwest::StatusCode),
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Streams `body` through the configured codec into `path`, rather than
+/// buffering the whole compressed output before writing it out.
+async fn write_compressed_body(
+    path: &str,
+    codec: CompressionCodec,
+    body: &[u8],
+) -> std::io::Result<()> {
+    let file = tokio::fs::File::create(path).await?;
+    match codec {
+        CompressionCodec::Zstd => {
+            let mut encoder = ZstdEncoder::new(file);
+            encoder.write_all(body).await?;
+            encoder.shutdown().await?;
+        }
+        CompressionCodec::Gzip => {
+            let mut encoder = GzipEncoder::new(file);
+            encoder.write_all(body).await?;
+            encoder.shutdown().await?;
+        }
+        CompressionCodec::Brotli => {
+            let mut encoder = BrotliEncoder::new(file);
+            encoder.write_all(body).await?;
+            encoder.shutdown().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Gutenberg will gzip the response when asked; decoding it here instead of
+/// letting it arrive uncompressed roughly halves download bandwidth.
+async fn decode_gzip(bytes: &[u8]) -> std::io::Result<String> {
+    let mut decoder = GzipDecoder::new(BufReader::new(bytes));
+    let mut out = String::new();
+    decoder.read_to_string(&mut out).await?;
+    Ok(out)
+}
+
+pub async fn download_book(book_id: u32) -> Result<String, DownloadError> {
     let url = format!(
         "https://www.gutenberg.org/cache/epub/{}/pg{}.txt",
         book_id, book_id
@@ -14,9 +66,10 @@ pub async fn download_book(
     fs::create_dir_all(&datalake_path)?;
 
     let header_path = format!("{}/header_{}.txt", datalake_path, book_id);
-    let body_path = format!("{}/body_{}.txt", datalake_path, book_id);
 
-    if std::path::Path::new(&header_path).exists() && std::path::Path::new(&body_path).exists() {
+    if std::path::Path::new(&header_path).exists()
+        && existing_body_path(&datalake_path, book_id).is_some()
+    {
         info!("Book {} already exists, skipping download", book_id);
         return Ok(datalake_path);
     }
@@ -24,17 +77,44 @@ pub async fn download_book(
     info!("Downloading book {} from {}", book_id, url);
 
     let client = reqwest::Client::new();
-    let response = client.get(&url).send().await?;
+    let response = client
+        .get(&url)
+        .header(ACCEPT_ENCODING, "gzip")
+        .send()
+        .await?;
 
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(DownloadError::NotFound(book_id));
+    }
     if !response.status().is_success() {
-        return Err(format!("Failed to download book {}: {}", book_id, response.status()).into());
+        return Err(DownloadError::UpstreamStatus(book_id, response.status()));
     }
 
-    let text = response.text().await?;
+    let is_gzip = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("gzip"))
+        .unwrap_or(false);
+    let raw = response.bytes().await?;
+    let text = if is_gzip {
+        decode_gzip(&raw).await?
+    } else {
+        String::from_utf8_lossy(&raw).to_string()
+    };
+
     let (header, body) = header_body_split(&text);
 
+    let codec = CompressionCodec::from_env();
+    let body_path = format!(
+        "{}/body_{}.txt.{}",
+        datalake_path,
+        book_id,
+        codec.extension()
+    );
+
     fs::write(&header_path, header)?;
-    fs::write(&body_path, body)?;
+    write_compressed_body(&body_path, codec, body.as_bytes()).await?;
 
     info!(
         "Successfully downloaded book {} to {}",
@@ -0,0 +1,96 @@
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::timeout;
+use tracing::warn;
+
+/// How long to wait for indexing-service to report a book finished indexing
+/// before giving up and marking the ingest task failed. Generous, since a
+/// large book body can take a while to tokenize and the indexing worker only
+/// flushes its queue every couple of seconds.
+const INDEX_WAIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn indexing_service_url() -> String {
+    std::env::var("INDEXING_SERVICE_URL")
+        .unwrap_or_else(|_| "http://indexing-service:7002".to_string())
+}
+
+#[derive(Debug, Error)]
+pub enum IndexingError {
+    #[error("failed to reach indexing-service: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("indexing-service reported book {0} failed to index")]
+    Failed(u32),
+    #[error("timed out after {1:?} waiting for book {0} to finish indexing")]
+    Timeout(u32, Duration),
+    #[error("indexing-service event stream closed before book {0} finished indexing")]
+    StreamClosed(u32),
+}
+
+#[derive(Debug, Deserialize)]
+struct ProgressEvent {
+    book_id: u32,
+    status: String,
+}
+
+/// Enqueues `book_id` on indexing-service's own queue (`POST
+/// /index/update/:book_id`) and then watches its `/index/events` SSE stream
+/// for this book's terminal status, so a caller only gets a success result
+/// once the book is actually searchable - not the moment its file lands on
+/// disk.
+pub async fn index_and_wait(book_id: u32) -> Result<(), IndexingError> {
+    let client = reqwest::Client::new();
+    let base = indexing_service_url();
+
+    client
+        .post(format!("{}/index/update/{}", base, book_id))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    match timeout(INDEX_WAIT_TIMEOUT, watch_for_completion(&client, &base, book_id)).await {
+        Ok(result) => result,
+        Err(_) => Err(IndexingError::Timeout(book_id, INDEX_WAIT_TIMEOUT)),
+    }
+}
+
+async fn watch_for_completion(
+    client: &reqwest::Client,
+    base: &str,
+    book_id: u32,
+) -> Result<(), IndexingError> {
+    let response = client.get(format!("{}/index/events", base)).send().await?;
+    let mut stream = response.bytes_stream();
+    let mut buf = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        buf.push_str(&String::from_utf8_lossy(&chunk?));
+
+        while let Some(pos) = buf.find('\n') {
+            let line = buf[..pos].trim_end_matches('\r').to_string();
+            buf.drain(..=pos);
+
+            let Some(payload) = line.strip_prefix("data: ") else {
+                continue;
+            };
+            let event: ProgressEvent = match serde_json::from_str(payload) {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Ignoring malformed progress event: {}", e);
+                    continue;
+                }
+            };
+            if event.book_id != book_id {
+                continue;
+            }
+            match event.status.as_str() {
+                "indexed" => return Ok(()),
+                "failed" => return Err(IndexingError::Failed(book_id)),
+                _ => {}
+            }
+        }
+    }
+
+    Err(IndexingError::StreamClosed(book_id))
+}
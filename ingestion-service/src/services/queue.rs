@@ -0,0 +1,100 @@
+use crate::models::task::TaskRegistry;
+use crate::services::download::{download_book, DownloadError};
+use crate::services::indexing::index_and_wait;
+use chrono::Utc;
+use tokio::sync::mpsc;
+use tokio::sync::Semaphore;
+use tokio::time::{sleep, Duration};
+use tracing::{error, warn};
+
+/// Caps how many downloads run at once so a burst of `/ingest/:book_id`
+/// calls can't saturate outbound connections to Project Gutenberg.
+const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct IngestJob {
+    pub task_id: u64,
+    pub book_id: u32,
+}
+
+/// Drains the ingest queue, running up to `MAX_CONCURRENT_DOWNLOADS` downloads
+/// at a time and retrying transient failures with backoff. Runs for the
+/// lifetime of the service, spawned once from `main`.
+pub async fn run_worker(mut jobs: mpsc::Receiver<IngestJob>, registry: TaskRegistry) {
+    let semaphore = std::sync::Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS));
+
+    while let Some(job) = jobs.recv().await {
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let registry = registry.clone();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            process_job(job, registry).await;
+        });
+    }
+}
+
+async fn process_job(job: IngestJob, registry: TaskRegistry) {
+    set_status(&registry, job.task_id, "downloading", None, None);
+
+    let mut last_error = None;
+    for attempt in 1..=MAX_ATTEMPTS {
+        match download_book(job.book_id).await {
+            Ok(path) => {
+                set_status(&registry, job.task_id, "indexing", Some(path.clone()), None);
+                match index_and_wait(job.book_id).await {
+                    Ok(()) => set_status(&registry, job.task_id, "succeeded", Some(path), None),
+                    Err(e) => {
+                        warn!(
+                            "Task {} downloaded book {} but indexing failed: {}",
+                            job.task_id, job.book_id, e
+                        );
+                        set_status(&registry, job.task_id, "failed", Some(path), Some(e.to_string()));
+                    }
+                }
+                return;
+            }
+            Err(DownloadError::NotFound(id)) => {
+                set_status(
+                    &registry,
+                    job.task_id,
+                    "failed",
+                    None,
+                    Some(format!("book {} not found", id)),
+                );
+                return;
+            }
+            Err(e) => {
+                warn!(
+                    "Attempt {}/{} to download book {} failed: {}",
+                    attempt, MAX_ATTEMPTS, job.book_id, e
+                );
+                last_error = Some(e.to_string());
+                if attempt < MAX_ATTEMPTS {
+                    sleep(Duration::from_millis(500 * attempt as u64)).await;
+                }
+            }
+        }
+    }
+
+    error!(
+        "Task {} giving up on book {} after {} attempts",
+        job.task_id, job.book_id, MAX_ATTEMPTS
+    );
+    set_status(&registry, job.task_id, "failed", None, last_error);
+}
+
+fn set_status(
+    registry: &TaskRegistry,
+    task_id: u64,
+    status: &str,
+    path: Option<String>,
+    error: Option<String>,
+) {
+    if let Some(task) = registry.lock().unwrap().get_mut(&task_id) {
+        task.status = status.to_string();
+        task.path = path;
+        task.error = error;
+        task.updated_at = Utc::now();
+    }
+}
@@ -2,6 +2,33 @@ use chrono::Utc;
 
 pub const DATALAKE_PATH: &str = "/app/datalake";
 
+/// Codec used for compressed datalake bodies. Selected once at startup via
+/// `DATALAKE_COMPRESSION` (`zstd` (default), `gzip`, or `brotli`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionCodec {
+    Zstd,
+    Gzip,
+    Brotli,
+}
+
+impl CompressionCodec {
+    pub fn from_env() -> Self {
+        match std::env::var("DATALAKE_COMPRESSION") {
+            Ok(v) if v.eq_ignore_ascii_case("gzip") => CompressionCodec::Gzip,
+            Ok(v) if v.eq_ignore_ascii_case("brotli") => CompressionCodec::Brotli,
+            _ => CompressionCodec::Zstd,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CompressionCodec::Zstd => "zst",
+            CompressionCodec::Gzip => "gz",
+            CompressionCodec::Brotli => "br",
+        }
+    }
+}
+
 pub fn header_body_split(text: &str) -> (String, String) {
     let start_marker = "*** START OF THE PROJECT GUTENBERG EBOOK";
     let end_marker = "*** END OF THE PROJECT GUTENBERG EBOOK";
@@ -28,3 +55,20 @@ pub fn create_datalake_path(book_id: u32) -> String {
     let subdir = format!("{:02}", book_id % 100);
     format!("{}/{}/{}", DATALAKE_PATH, date_str, subdir)
 }
+
+/// Finds an existing body for `book_id` under `datalake_path`, preferring a
+/// plaintext file over any compressed variant so books downloaded before
+/// compression was enabled keep working.
+pub fn existing_body_path(datalake_path: &str, book_id: u32) -> Option<String> {
+    let plain = format!("{}/body_{}.txt", datalake_path, book_id);
+    if std::path::Path::new(&plain).exists() {
+        return Some(plain);
+    }
+    for ext in ["zst", "gz", "br"] {
+        let compressed = format!("{}/body_{}.txt.{}", datalake_path, book_id, ext);
+        if std::path::Path::new(&compressed).exists() {
+            return Some(compressed);
+        }
+    }
+    None
+}
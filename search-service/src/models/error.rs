@@ -0,0 +1,35 @@
+use axum::http::StatusCode;
+pub use common_error::ApiError;
+
+/// This service's error constructors, added via an extension trait since
+/// `ApiError` itself now lives in `common_error` and inherent impls can only
+/// be added from the crate that defines the type.
+pub trait ApiErrorExt {
+    fn invalid_search_query(reason: impl Into<String>) -> Self;
+    fn invalid_filter(offset: usize, reason: impl std::fmt::Display) -> Self;
+    fn index_backend_unavailable(source: impl std::fmt::Display) -> Self;
+}
+
+impl ApiErrorExt for ApiError {
+    fn invalid_search_query(reason: impl Into<String>) -> Self {
+        ApiError::new(StatusCode::BAD_REQUEST, "invalid_search_query", "validation_error", reason)
+    }
+
+    fn invalid_filter(offset: usize, reason: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_filter",
+            "validation_error",
+            format!("{} (at character {})", reason, offset),
+        )
+    }
+
+    fn index_backend_unavailable(source: impl std::fmt::Display) -> Self {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "index_backend_unavailable",
+            "backend_error",
+            format!("Index storage backend error: {}", source),
+        )
+    }
+}
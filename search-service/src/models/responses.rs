@@ -14,6 +14,7 @@ pub struct BookResult {
     pub author: String,
     pub language: String,
     pub year: Option<u32>,
+    pub score: Option<f32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,4 +23,8 @@ pub struct SearchResponse {
     pub filters: HashMap<String, String>,
     pub count: usize,
     pub results: Vec<BookResult>,
+    pub facet_distribution: HashMap<String, HashMap<String, usize>>,
+    pub limit: usize,
+    pub offset: usize,
+    pub estimated_total_hits: usize,
 }
\ No newline at end of file
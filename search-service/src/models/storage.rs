@@ -0,0 +1,520 @@
+use async_trait::async_trait;
+use deadpool_redis::{Config as RedisConfig, Pool as RedisPool, PoolConfig, Runtime};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashSet;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+    #[error("PostgreSQL error: {0}")]
+    Postgres(#[from] sqlx::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Connection error: {0}")]
+    Connection(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookMetadata {
+    pub book_id: u32,
+    pub title: String,
+    pub author: String,
+    pub language: String,
+    pub year: Option<u32>,
+    pub word_count: usize,
+    pub unique_words: usize,
+}
+
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+/// Per-word postings gathered for ranking: for each matched query word, the
+/// `(book_id, term_frequency, doc_length)` triples of every book containing
+/// it. Each backend fills this in with a fixed number of round trips for the
+/// whole query (not one per word or per `(word, book)` pair) so
+/// `score_bm25`/`score_tfidf` below never have to
+/// await anything - they're pure scoring math shared by both backends.
+type PostingsByWord = std::collections::HashMap<String, Vec<(u32, f32, f32)>>;
+
+/// BM25 (k1=1.2, b=0.75), summing each word's score independently per the
+/// standard multi-term formula.
+fn score_bm25(n: f32, avgdl: f32, postings: &PostingsByWord) -> Vec<(u32, f32)> {
+    let avgdl = avgdl.max(1.0);
+    let mut scores: std::collections::HashMap<u32, f32> = std::collections::HashMap::new();
+
+    for book_postings in postings.values() {
+        let df = book_postings.len() as f32;
+        if df == 0.0 {
+            continue;
+        }
+        let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+
+        for &(book_id, tf, dl) in book_postings {
+            if tf == 0.0 {
+                continue;
+            }
+            let denom = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * (dl / avgdl));
+            *scores.entry(book_id).or_insert(0.0) += idf * (tf * (BM25_K1 + 1.0)) / denom;
+        }
+    }
+
+    sort_ranked(scores)
+}
+
+/// Classic TF-IDF: `idf(t) = ln(N / df[t])`, `score = sum over query terms
+/// of (1 + ln(tf[t][book])) * idf(t)`. Simpler than `score_bm25` (no
+/// document-length normalization), offered as a second scoring mode rather
+/// than a replacement since existing callers already depend on BM25
+/// ordering.
+fn score_tfidf(n: f32, postings: &PostingsByWord) -> Vec<(u32, f32)> {
+    let mut scores: std::collections::HashMap<u32, f32> = std::collections::HashMap::new();
+
+    for book_postings in postings.values() {
+        let df = book_postings.len() as f32;
+        if df == 0.0 {
+            continue;
+        }
+        let idf = (n / df).ln();
+
+        for &(book_id, tf, _dl) in book_postings {
+            if tf == 0.0 {
+                continue;
+            }
+            *scores.entry(book_id).or_insert(0.0) += (1.0 + tf.ln()) * idf;
+        }
+    }
+
+    sort_ranked(scores)
+}
+
+fn sort_ranked(scores: std::collections::HashMap<u32, f32>) -> Vec<(u32, f32)> {
+    let mut ranked: Vec<(u32, f32)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    ranked
+}
+
+#[async_trait]
+pub trait StorageBackend {
+    async fn get_book_metadata(&self, book_id: u32) -> Result<Option<BookMetadata>, StorageError>;
+    async fn get_indexed_books(&self) -> Result<HashSet<u32>, StorageError>;
+    async fn search_word(&self, word: &str) -> Result<HashSet<u32>, StorageError>;
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError>;
+    async fn get_stats(&self) -> Result<(usize, usize), StorageError>; // (total_books, unique_words)
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError>;
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError>;
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError>;
+    async fn test_connection(&self) -> Result<(), StorageError>;
+
+    /// Ranks books matching any of `words` by BM25. Each backend batches its
+    /// own postings fetch (a Redis pipeline, a single joined SQL query) into
+    /// a `PostingsByWord` and hands it to the shared `score_bm25` - there is
+    /// no generic default here, since a per-item-await implementation in
+    /// terms of `search_word`/`get_term_frequency` would be an O(terms *
+    /// matching_books) round-trip anti-pattern no backend should fall back
+    /// to by accident.
+    async fn search_ranked(&self, words: &[&str]) -> Result<Vec<(u32, f32)>, StorageError>;
+
+    /// Same contract as `search_ranked`, scored with `score_tfidf` instead.
+    async fn search_ranked_tfidf(&self, words: &[&str]) -> Result<Vec<(u32, f32)>, StorageError>;
+}
+
+pub struct RedisBackend {
+    pool: RedisPool,
+}
+
+/// Defaults chosen so a small deployment doesn't need to tune anything: one
+/// connection per core is enough to keep the search path (all reads) from
+/// queuing behind itself, and a 5s checkout timeout surfaces a starved pool
+/// as an error instead of hanging the request.
+fn default_pool_size() -> usize {
+    num_cpus::get()
+}
+
+const DEFAULT_POOL_TIMEOUT_SECS: u64 = 5;
+
+impl RedisBackend {
+    pub fn new(redis_url: &str) -> Result<Self, StorageError> {
+        let pool_size = std::env::var("REDIS_POOL_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(default_pool_size);
+        let wait_timeout_secs = std::env::var("REDIS_POOL_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_POOL_TIMEOUT_SECS);
+
+        let mut pool_config = PoolConfig::new(pool_size);
+        pool_config.timeouts.wait = Some(Duration::from_secs(wait_timeout_secs));
+
+        let mut config = RedisConfig::from_url(redis_url);
+        config.pool = Some(pool_config);
+
+        let pool = config
+            .create_pool(Some(Runtime::Tokio1))
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(Self { pool })
+    }
+
+    pub async fn get_connection(&self) -> Result<deadpool_redis::Connection, StorageError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+
+    /// Gathers everything `score_bm25`/`score_tfidf` need for every query
+    /// word in three pipelined round trips total (one SMEMBERS batch, one
+    /// HGET batch, one GET batch) instead of one SMEMBERS plus two awaits
+    /// per matching `(word, book)` pair.
+    async fn fetch_postings(&self, words: &[&str]) -> Result<PostingsByWord, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let mut members_pipe = redis::pipe();
+        for word in words {
+            members_pipe.cmd("SMEMBERS").arg(format!("word:{}", word));
+        }
+        let word_members: Vec<Vec<u32>> = members_pipe.query_async(&mut conn).await?;
+
+        let mut pairs: Vec<(&str, u32)> = Vec::new();
+        for (word, book_ids) in words.iter().zip(&word_members) {
+            for &book_id in book_ids {
+                pairs.push((word, book_id));
+            }
+        }
+
+        let mut tf_pipe = redis::pipe();
+        for &(word, book_id) in &pairs {
+            tf_pipe
+                .cmd("HGET")
+                .arg(format!("word:{}:tf", word))
+                .arg(book_id);
+        }
+        let term_frequencies: Vec<Option<i64>> = if pairs.is_empty() {
+            Vec::new()
+        } else {
+            tf_pipe.query_async(&mut conn).await?
+        };
+
+        let book_ids: HashSet<u32> = pairs.iter().map(|&(_, book_id)| book_id).collect();
+        let book_ids: Vec<u32> = book_ids.into_iter().collect();
+        let mut metadata_pipe = redis::pipe();
+        for &book_id in &book_ids {
+            metadata_pipe
+                .cmd("GET")
+                .arg(format!("book:{}:metadata", book_id));
+        }
+        let metadata_blobs: Vec<Option<Vec<u8>>> = if book_ids.is_empty() {
+            Vec::new()
+        } else {
+            metadata_pipe.query_async(&mut conn).await?
+        };
+
+        let mut doc_lengths: std::collections::HashMap<u32, f32> =
+            std::collections::HashMap::new();
+        for (book_id, blob) in book_ids.into_iter().zip(metadata_blobs) {
+            if let Some(bytes) = blob {
+                let bytes = crate::utils::blob_compression::decompress(&bytes);
+                if let Ok(metadata) = serde_json::from_slice::<BookMetadata>(&bytes) {
+                    doc_lengths.insert(book_id, metadata.word_count as f32);
+                }
+            }
+        }
+
+        let mut postings: PostingsByWord = std::collections::HashMap::new();
+        for ((word, book_id), tf) in pairs.into_iter().zip(term_frequencies) {
+            let tf = tf.unwrap_or(0).max(0) as f32;
+            let dl = match doc_lengths.get(&book_id) {
+                Some(&dl) => dl,
+                None => continue,
+            };
+            postings
+                .entry(word.to_string())
+                .or_default()
+                .push((book_id, tf, dl));
+        }
+
+        Ok(postings)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for RedisBackend {
+    async fn get_book_metadata(&self, book_id: u32) -> Result<Option<BookMetadata>, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let key = format!("book:{}:metadata", book_id);
+        let value: Option<Vec<u8>> = conn.get(&key).await?;
+
+        match value {
+            Some(bytes) => {
+                let bytes = crate::utils::blob_compression::decompress(&bytes);
+                let metadata: BookMetadata = serde_json::from_slice(&bytes)?;
+                Ok(Some(metadata))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn get_indexed_books(&self) -> Result<HashSet<u32>, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let pattern = "book:*:metadata";
+        let keys: Vec<String> = conn.keys(pattern).await?;
+
+        let mut book_ids = HashSet::new();
+        for key in keys {
+            if let Some(book_id_str) = key
+                .strip_prefix("book:")
+                .and_then(|s| s.strip_suffix(":metadata"))
+            {
+                if let Ok(book_id) = book_id_str.parse::<u32>() {
+                    book_ids.insert(book_id);
+                }
+            }
+        }
+
+        Ok(book_ids)
+    }
+
+    async fn search_word(&self, word: &str) -> Result<HashSet<u32>, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let word_key = format!("word:{}", word);
+        let book_ids: Vec<u32> = conn.smembers(&word_key).await?;
+
+        Ok(book_ids.into_iter().collect())
+    }
+
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let tf_key = format!("word:{}:tf", word);
+        let tf: Option<i64> = conn.hget(&tf_key, book_id).await?;
+
+        Ok(tf.unwrap_or(0).max(0) as usize)
+    }
+
+    async fn get_stats(&self) -> Result<(usize, usize), StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let total_books: Option<usize> = conn.get("stats:total_books").await?;
+        let unique_words: usize = conn.scard("stats:all_words").await?;
+
+        Ok((total_books.unwrap_or(0), unique_words))
+    }
+
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError> {
+        let mut conn = self.get_connection().await?;
+
+        let total_books: Option<usize> = conn.get("stats:total_books").await?;
+        let total_word_count: Option<usize> = conn.get("stats:total_word_count").await?;
+
+        let total_books = total_books.unwrap_or(0);
+        if total_books == 0 {
+            return Ok(0.0);
+        }
+
+        Ok(total_word_count.unwrap_or(0) as f64 / total_books as f64)
+    }
+
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let mut conn = self.get_connection().await?;
+        let bytes: Option<Vec<u8>> = conn.get("index:vocabulary_fst").await?;
+        Ok(bytes)
+    }
+
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let mut conn = self.get_connection().await?;
+        let bytes: Option<Vec<u8>> = conn.get("index:correction_index").await?;
+        Ok(bytes)
+    }
+
+    async fn test_connection(&self) -> Result<(), StorageError> {
+        let mut conn = self.get_connection().await?;
+        let _: Option<String> = conn.get("__connection_test__").await?;
+        Ok(())
+    }
+
+    async fn search_ranked(&self, words: &[&str]) -> Result<Vec<(u32, f32)>, StorageError> {
+        let (total_books, _) = self.get_stats().await?;
+        let avgdl = self.get_avg_doc_length().await?;
+        let postings = self.fetch_postings(words).await?;
+        Ok(score_bm25(total_books as f32, avgdl as f32, &postings))
+    }
+
+    async fn search_ranked_tfidf(&self, words: &[&str]) -> Result<Vec<(u32, f32)>, StorageError> {
+        let (total_books, _) = self.get_stats().await?;
+        let postings = self.fetch_postings(words).await?;
+        Ok(score_tfidf(total_books as f32, &postings))
+    }
+}
+
+pub struct PostgresBackend {
+    pool: PgPool,
+}
+
+impl PostgresBackend {
+    pub async fn new(database_url: &str) -> Result<Self, StorageError> {
+        let pool = PgPool::connect(database_url).await?;
+        Ok(Self { pool })
+    }
+
+    /// One joined query across `word_index`/`books` for every query word,
+    /// instead of a `search_word` plus a `get_term_frequency` round trip per
+    /// matching book.
+    async fn fetch_postings(&self, words: &[&str]) -> Result<PostingsByWord, StorageError> {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        let rows = sqlx::query(
+            "SELECT w.word, w.book_id, w.tf, b.word_count \
+             FROM word_index w JOIN books b ON b.book_id = w.book_id \
+             WHERE w.word = ANY($1)",
+        )
+        .bind(&words)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut postings: PostingsByWord = std::collections::HashMap::new();
+        for row in rows {
+            let word: String = row.get("word");
+            let book_id = row.get::<i32, _>("book_id") as u32;
+            let tf = row.get::<i32, _>("tf") as f32;
+            let dl = row.get::<i32, _>("word_count") as f32;
+            postings.entry(word).or_default().push((book_id, tf, dl));
+        }
+
+        Ok(postings)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for PostgresBackend {
+    // Typed relational columns, not a serialized blob - nothing here for
+    // `blob_compression` to decode, unlike `RedisBackend`'s JSON value.
+    async fn get_book_metadata(&self, book_id: u32) -> Result<Option<BookMetadata>, StorageError> {
+        let row = sqlx::query(
+            "SELECT book_id, title, author, language, year, word_count, unique_words FROM books WHERE book_id = $1"
+        )
+        .bind(book_id as i32)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let metadata = BookMetadata {
+                    book_id: row.get::<i32, _>("book_id") as u32,
+                    title: row.get("title"),
+                    author: row.get("author"),
+                    language: row.get("language"),
+                    year: row.get::<Option<i32>, _>("year").map(|y| y as u32),
+                    word_count: row.get::<i32, _>("word_count") as usize,
+                    unique_words: row.get::<i32, _>("unique_words") as usize,
+                };
+                Ok(Some(metadata))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn get_indexed_books(&self) -> Result<HashSet<u32>, StorageError> {
+        let rows = sqlx::query("SELECT book_id FROM books")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let book_ids = rows
+            .into_iter()
+            .map(|row| row.get::<i32, _>("book_id") as u32)
+            .collect();
+
+        Ok(book_ids)
+    }
+
+    async fn search_word(&self, word: &str) -> Result<HashSet<u32>, StorageError> {
+        let rows = sqlx::query("SELECT book_id FROM word_index WHERE word = $1")
+            .bind(word)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let book_ids = rows
+            .into_iter()
+            .map(|row| row.get::<i32, _>("book_id") as u32)
+            .collect();
+
+        Ok(book_ids)
+    }
+
+    async fn get_term_frequency(&self, word: &str, book_id: u32) -> Result<usize, StorageError> {
+        let row = sqlx::query("SELECT tf FROM word_index WHERE word = $1 AND book_id = $2")
+            .bind(word)
+            .bind(book_id as i32)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get::<i32, _>("tf") as usize).unwrap_or(0))
+    }
+
+    async fn get_stats(&self) -> Result<(usize, usize), StorageError> {
+        let total_books = sqlx::query("SELECT COUNT(*) as count FROM books")
+            .fetch_one(&self.pool)
+            .await?
+            .get::<i64, _>("count") as usize;
+
+        let unique_words = sqlx::query("SELECT COUNT(DISTINCT word) as count FROM word_index")
+            .fetch_one(&self.pool)
+            .await?
+            .get::<i64, _>("count") as usize;
+
+        Ok((total_books, unique_words))
+    }
+
+    async fn get_avg_doc_length(&self) -> Result<f64, StorageError> {
+        let row = sqlx::query("SELECT COALESCE(AVG(word_count)::float8, 0) as avgdl FROM books")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get::<f64, _>("avgdl"))
+    }
+
+    async fn get_vocabulary_fst(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let row = sqlx::query("SELECT data FROM vocabulary_fst WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("data")))
+    }
+
+    async fn get_correction_index(&self) -> Result<Option<Vec<u8>>, StorageError> {
+        let row = sqlx::query("SELECT data FROM correction_index WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("data")))
+    }
+
+    async fn test_connection(&self) -> Result<(), StorageError> {
+        sqlx::query("SELECT 1").fetch_one(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn search_ranked(&self, words: &[&str]) -> Result<Vec<(u32, f32)>, StorageError> {
+        let (total_books, _) = self.get_stats().await?;
+        let avgdl = self.get_avg_doc_length().await?;
+        let postings = self.fetch_postings(words).await?;
+        Ok(score_bm25(total_books as f32, avgdl as f32, &postings))
+    }
+
+    async fn search_ranked_tfidf(&self, words: &[&str]) -> Result<Vec<(u32, f32)>, StorageError> {
+        let (total_books, _) = self.get_stats().await?;
+        let postings = self.fetch_postings(words).await?;
+        Ok(score_tfidf(total_books as f32, &postings))
+    }
+}
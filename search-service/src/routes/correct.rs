@@ -0,0 +1,85 @@
+use crate::models::error::{ApiError, ApiErrorExt};
+use crate::models::storage::StorageBackend;
+use crate::utils::spellcheck::{self, CorrectionIndex};
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::error;
+
+type Backend = Arc<dyn StorageBackend + Send + Sync>;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Serialize)]
+pub struct CorrectionSuggestion {
+    pub term: String,
+    pub distance: u32,
+    pub document_frequency: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorrectionResponse {
+    pub query: String,
+    pub suggestions: Vec<CorrectionSuggestion>,
+}
+
+/// Loads the correction index persisted by the indexing service. Missing or
+/// corrupt indexes degrade gracefully to "no suggestions" rather than
+/// failing the request, matching `load_vocabulary_fst`'s approach in search.
+async fn load_correction_index(backend: &Backend) -> CorrectionIndex {
+    match backend.get_correction_index().await {
+        Ok(Some(bytes)) => match spellcheck::deserialize(&bytes) {
+            Ok(index) => index,
+            Err(e) => {
+                error!("Correction index is corrupt: {}", e);
+                CorrectionIndex::default()
+            }
+        },
+        Ok(None) => CorrectionIndex::default(),
+        Err(e) => {
+            error!("Failed to load correction index: {}", e);
+            CorrectionIndex::default()
+        }
+    }
+}
+
+pub async fn correct_term(
+    Path(term): Path<String>,
+    State(backend): State<Backend>,
+) -> Result<Json<CorrectionResponse>, ApiError> {
+    let term = term.to_lowercase();
+    let index = load_correction_index(&backend).await;
+    let candidates = index.correct(&term);
+
+    let mut suggestions = Vec::with_capacity(candidates.len());
+    for (candidate, distance) in candidates {
+        let document_frequency = backend
+            .search_word(&candidate)
+            .await
+            .map(|book_ids| book_ids.len())
+            .unwrap_or(0);
+        suggestions.push(CorrectionSuggestion {
+            term: candidate,
+            distance,
+            document_frequency,
+        });
+    }
+
+    // Edit distance first, then prefer terms seen in more books, ties broken
+    // alphabetically for determinism.
+    suggestions.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then_with(|| b.document_frequency.cmp(&a.document_frequency))
+            .then_with(|| a.term.cmp(&b.term))
+    });
+    suggestions.truncate(MAX_SUGGESTIONS);
+
+    Ok(Json(CorrectionResponse {
+        query: term,
+        suggestions,
+    }))
+}
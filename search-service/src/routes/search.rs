@@ -1,10 +1,14 @@
+use crate::models::error::{ApiError, ApiErrorExt};
 use crate::models::responses::{BookResult, SearchResponse};
 use crate::models::storage::{BookMetadata, StorageBackend};
+use crate::utils::filter::{parse_filter, ComparisonOp, FilterCondition, FilterValue};
+use crate::utils::fuzzy::expand_fuzzy_candidates;
+use crate::utils::tokenize::tokenize_query;
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
     response::Json,
 };
+use fst::Set;
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -16,51 +20,209 @@ pub struct SearchParams {
     pub author: Option<String>,
     pub language: Option<String>,
     pub year: Option<u32>,
+    pub filter: Option<String>,
+    pub facets: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub rank: Option<String>,
 }
 
-type Backend = Arc<dyn StorageBackend + Send + Sync>;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RankMode {
+    Bm25,
+    TfIdf,
+}
+
+fn parse_rank_mode(rank: &Option<String>) -> RankMode {
+    match rank.as_deref() {
+        Some("tfidf") => RankMode::TfIdf,
+        _ => RankMode::Bm25,
+    }
+}
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 1000;
+
+fn normalize_pagination(limit: Option<usize>, offset: Option<usize>) -> (usize, usize) {
+    (
+        limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT),
+        offset.unwrap_or(0),
+    )
+}
+
+const MAX_FACET_VALUES: usize = 10;
 
-fn tokenize_query(query: &str) -> Vec<String> {
-    query
-        .to_lowercase()
-        .split_whitespace()
-        .filter(|word| word.len() > 2)
-        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-        .filter(|word| !word.is_empty())
+fn parse_facet_fields(facets: &Option<String>) -> Vec<String> {
+    facets
+        .as_ref()
+        .map(|s| {
+            s.split(',')
+                .map(|field| field.trim().to_lowercase())
+                .filter(|field| !field.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn facet_value(book: &BookMetadata, field: &str) -> Option<String> {
+    match field {
+        "language" => Some(book.language.clone()),
+        "author" => Some(book.author.clone()),
+        "year" => book.year.map(|year| format!("{}s", (year / 10) * 10)),
+        _ => None,
+    }
+}
+
+/// Single pass over the filtered result set accumulating a per-field value
+/// distribution, capped to the top `MAX_FACET_VALUES` by count so the
+/// payload stays bounded for high-cardinality fields like `author`.
+fn compute_facets(
+    metadata_list: &[BookMetadata],
+    fields: &[String],
+) -> HashMap<String, HashMap<String, usize>> {
+    let mut counts: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    for field in fields {
+        counts.entry(field.clone()).or_default();
+    }
+
+    for book in metadata_list {
+        for field in fields {
+            if let Some(value) = facet_value(book, field) {
+                *counts.entry(field.clone()).or_default().entry(value).or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(field, values)| {
+            let mut pairs: Vec<(String, usize)> = values.into_iter().collect();
+            pairs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            pairs.truncate(MAX_FACET_VALUES);
+            (field, pairs.into_iter().collect())
+        })
         .collect()
 }
 
+/// Builds the full filter tree for a request: the parsed `filter` expression,
+/// ANDed with the legacy `author`/`language`/`year` params lowered into
+/// equivalent conditions, so old clients keep working unchanged.
+fn build_filter_condition(params: &SearchParams) -> Result<Option<FilterCondition>, ApiError> {
+    let mut parts = Vec::new();
+
+    if let Some(filter_str) = &params.filter {
+        let condition =
+            parse_filter(filter_str).map_err(|e| ApiError::invalid_filter(e.offset, e.message))?;
+        parts.push(condition);
+    }
+    if let Some(author) = &params.author {
+        parts.push(FilterCondition::Condition {
+            field: "author".to_string(),
+            op: ComparisonOp::Eq,
+            value: FilterValue::Text(author.clone()),
+        });
+    }
+    if let Some(language) = &params.language {
+        parts.push(FilterCondition::Condition {
+            field: "language".to_string(),
+            op: ComparisonOp::Eq,
+            value: FilterValue::Text(language.clone()),
+        });
+    }
+    if let Some(year) = params.year {
+        parts.push(FilterCondition::Condition {
+            field: "year".to_string(),
+            op: ComparisonOp::Eq,
+            value: FilterValue::Number(year as f64),
+        });
+    }
+
+    Ok(parts
+        .into_iter()
+        .reduce(|a, b| FilterCondition::And(Box::new(a), Box::new(b))))
+}
+
+type Backend = Arc<dyn StorageBackend + Send + Sync>;
+
 // No longer needed - we get year directly from metadata
 
+/// Loads the vocabulary FST persisted by the indexing service. Missing or
+/// corrupt FSTs degrade gracefully to exact-match-only search rather than
+/// failing the request.
+async fn load_vocabulary_fst(backend: &Backend) -> Option<Set<Vec<u8>>> {
+    match backend.get_vocabulary_fst().await {
+        Ok(Some(bytes)) => match Set::new(bytes) {
+            Ok(set) => Some(set),
+            Err(e) => {
+                error!("Vocabulary FST is corrupt: {}", e);
+                None
+            }
+        },
+        Ok(None) => None,
+        Err(e) => {
+            error!("Failed to load vocabulary FST: {}", e);
+            None
+        }
+    }
+}
+
+/// Expands a query token to its exact form plus any dictionary terms within
+/// the token's edit-distance budget, exact match first so callers can prefer
+/// it when scoring.
+fn candidate_terms(word: &str, vocabulary: Option<&Set<Vec<u8>>>) -> Vec<String> {
+    let mut terms = vec![word.to_string()];
+
+    if let Some(vocabulary) = vocabulary {
+        for candidate in expand_fuzzy_candidates(vocabulary, word) {
+            if candidate.term != word {
+                terms.push(candidate.term);
+            }
+        }
+    }
+
+    terms
+}
+
+/// Resolves each query word to the set of matching books plus the dictionary
+/// terms (exact + fuzzy) that were searched for it, so a caller can later
+/// score a book using whichever term actually matched.
 async fn get_book_ids_for_words(
     words: &[String],
     backend: &Backend,
-) -> Result<HashSet<u32>, StatusCode> {
+) -> Result<(HashSet<u32>, Vec<(String, Vec<String>)>), ApiError> {
     if words.is_empty() {
-        return Ok(HashSet::new());
+        return Ok((HashSet::new(), Vec::new()));
     }
 
+    let vocabulary = load_vocabulary_fst(backend).await;
     let mut result_sets = Vec::new();
+    let mut term_expansions = Vec::new();
 
     for word in words {
-        match backend.search_word(word).await {
-            Ok(book_ids) => {
-                if book_ids.is_empty() {
-                    // If any word has no results, the intersection will be empty
-                    return Ok(HashSet::new());
+        let terms = candidate_terms(word, vocabulary.as_ref());
+        let mut book_ids = HashSet::new();
+
+        for term in &terms {
+            match backend.search_word(term).await {
+                Ok(ids) => book_ids.extend(ids),
+                Err(e) => {
+                    error!("Failed to search for word '{}': {}", term, e);
+                    return Err(ApiError::index_backend_unavailable(e));
                 }
-                result_sets.push(book_ids);
-            }
-            Err(e) => {
-                error!("Failed to search for word '{}': {}", word, e);
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
             }
         }
+
+        if book_ids.is_empty() {
+            // If any word (exact or fuzzy) has no results, the intersection will be empty
+            return Ok((HashSet::new(), term_expansions));
+        }
+        result_sets.push(book_ids);
+        term_expansions.push((word.clone(), terms));
     }
 
     // Find intersection of all sets (books that contain ALL words)
     if result_sets.is_empty() {
-        return Ok(HashSet::new());
+        return Ok((HashSet::new(), term_expansions));
     }
 
     let mut intersection = result_sets[0].clone();
@@ -68,7 +230,37 @@ async fn get_book_ids_for_words(
         intersection = intersection.intersection(set).cloned().collect();
     }
 
-    Ok(intersection)
+    Ok((intersection, term_expansions))
+}
+
+/// Flattens each query word's fuzzy expansion into the flat term list
+/// `StorageBackend::search_ranked` expects.
+fn flatten_terms(term_expansions: &[(String, Vec<String>)]) -> Vec<&str> {
+    term_expansions
+        .iter()
+        .flat_map(|(_, expansions)| expansions.iter().map(|term| term.as_str()))
+        .collect()
+}
+
+/// Ranks `book_ids` by the requested scoring mode without fetching their
+/// full metadata, so an unfiltered/unfaceted request can slice down to a
+/// single page *before* paying for `get_book_metadata_batch`.
+async fn rank_book_ids(
+    term_expansions: &[(String, Vec<String>)],
+    book_ids: &HashSet<u32>,
+    backend: &Backend,
+    rank_mode: RankMode,
+) -> Vec<(u32, f32)> {
+    let terms = flatten_terms(term_expansions);
+    let ranked = match rank_mode {
+        RankMode::Bm25 => backend.search_ranked(&terms).await,
+        RankMode::TfIdf => backend.search_ranked_tfidf(&terms).await,
+    };
+    ranked
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(book_id, _)| book_ids.contains(book_id))
+        .collect()
 }
 
 async fn get_book_metadata_batch(
@@ -94,61 +286,65 @@ async fn get_book_metadata_batch(
 
 fn apply_filters(
     metadata_list: Vec<BookMetadata>,
-    params: &SearchParams,
+    condition: Option<&FilterCondition>,
 ) -> Vec<BookMetadata> {
-    metadata_list
-        .into_iter()
-        .filter(|book| {
-            // Apply author filter
-            if let Some(ref author_filter) = params.author {
-                if !book
-                    .author
-                    .to_lowercase()
-                    .contains(&author_filter.to_lowercase())
-                {
-                    return false;
-                }
-            }
-
-            // Apply language filter
-            if let Some(ref language_filter) = params.language {
-                if book.language != *language_filter {
-                    return false;
-                }
-            }
+    match condition {
+        None => metadata_list,
+        Some(condition) => metadata_list
+            .into_iter()
+            .filter(|book| crate::utils::filter::evaluate(condition, book))
+            .collect(),
+    }
+}
 
-            // Apply year filter
-            if let Some(year_filter) = params.year {
-                if book.year != Some(year_filter) {
-                    return false;
-                }
-            }
+/// Scores books via the requested ranking mode's backend method.
+async fn compute_scores(
+    term_expansions: &[(String, Vec<String>)],
+    backend: &Backend,
+    rank_mode: RankMode,
+) -> HashMap<u32, f32> {
+    let terms = flatten_terms(term_expansions);
+    if terms.is_empty() {
+        return HashMap::new();
+    }
 
-            true
-        })
-        .collect()
+    let ranked = match rank_mode {
+        RankMode::Bm25 => backend.search_ranked(&terms).await,
+        RankMode::TfIdf => backend.search_ranked_tfidf(&terms).await,
+    };
+    ranked.unwrap_or_default().into_iter().collect()
 }
 
 pub async fn search_books(
     Query(params): Query<SearchParams>,
     State(backend): State<Backend>,
-) -> Result<Json<SearchResponse>, StatusCode> {
+) -> Result<Json<SearchResponse>, ApiError> {
     info!("Search query: {:?}", params);
 
-    // Tokenize the search query
-    let query_words = tokenize_query(&params.q);
+    if params.q.trim().is_empty() {
+        return Err(ApiError::invalid_search_query("Query must not be empty"));
+    }
+
+    // Tokenize the search query the same way the indexer tokenized book text,
+    // so stemmed query terms match stemmed index terms. The `language` param
+    // (already used to filter results) doubles as the stemming hint; absent
+    // it, queries stem as English.
+    let query_language = params.language.as_deref().unwrap_or("english");
+    let query_words = tokenize_query(&params.q, query_language);
 
     if query_words.is_empty() {
-        return Ok(Json(SearchResponse {
-            query: params.q.clone(),
-            filters: HashMap::new(),
-            count: 0,
-            results: Vec::new(),
-        }));
+        return Err(ApiError::invalid_search_query(
+            "Query must contain at least one searchable term longer than 2 characters that is not a stop word",
+        ));
     }
 
+    let filter_condition = build_filter_condition(&params)?;
+    let facet_fields = parse_facet_fields(&params.facets);
+    let (limit, offset) = normalize_pagination(params.limit, params.offset);
+    let rank_mode = parse_rank_mode(&params.rank);
+
     // Find books that contain all the search words
-    let book_ids = get_book_ids_for_words(&query_words, &backend).await?;
+    let (book_ids, term_expansions) = get_book_ids_for_words(&query_words, &backend).await?;
 
     if book_ids.is_empty() {
         return Ok(Json(SearchResponse {
@@ -156,29 +352,47 @@ pub async fn search_books(
             filters: build_filters_map(&params),
             count: 0,
             results: Vec::new(),
+            facet_distribution: HashMap::new(),
+            limit,
+            offset,
+            estimated_total_hits: 0,
         }));
     }
 
-    // Get metadata for all matching books
-    let all_metadata = get_book_metadata_batch(&book_ids, &backend).await;
+    // Filters and facets both need every matching book's metadata to
+    // evaluate, so only take the fetch-metadata-for-the-page-only fast path
+    // when neither is in play.
+    let (mut results, estimated_total_hits, facet_distribution) =
+        if filter_condition.is_none() && facet_fields.is_empty() {
+            let ranked = rank_book_ids(&term_expansions, &book_ids, &backend, rank_mode).await;
+            let total = ranked.len();
+            let page: Vec<(u32, f32)> = ranked.into_iter().skip(offset).take(limit).collect();
+            let page_ids: HashSet<u32> = page.iter().map(|(book_id, _)| *book_id).collect();
+            let scores: HashMap<u32, f32> = page.into_iter().collect();
+            let page_metadata = get_book_metadata_batch(&page_ids, &backend).await;
+            (to_results(page_metadata, &scores), total, HashMap::new())
+        } else {
+            let all_metadata = get_book_metadata_batch(&book_ids, &backend).await;
+            let filtered_metadata = apply_filters(all_metadata, filter_condition.as_ref());
+            let facet_distribution = compute_facets(&filtered_metadata, &facet_fields);
+            let scores = compute_scores(&term_expansions, &backend, rank_mode).await;
+            let total = filtered_metadata.len();
+            (to_results(filtered_metadata, &scores), total, facet_distribution)
+        };
 
-    // Apply filters
-    let filtered_metadata = apply_filters(all_metadata, &params);
-
-    // Convert to response format
-    let mut results: Vec<BookResult> = filtered_metadata
-        .into_iter()
-        .map(|book| BookResult {
-            book_id: book.book_id,
-            title: book.title.clone(),
-            author: book.author.clone(),
-            language: book.language.clone(),
-            year: book.year,
-        })
-        .collect();
+    // Most relevant first, ties broken by book_id for determinism
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.book_id.cmp(&b.book_id))
+    });
 
-    // Sort results by book_id for consistency
-    results.sort_by_key(|book| book.book_id);
+    // The slow path ranked the full filtered set, so it still needs slicing
+    // to a page; the fast path already fetched only the page.
+    if filter_condition.is_some() || !facet_fields.is_empty() {
+        results = results.into_iter().skip(offset).take(limit).collect();
+    }
 
     let filters = build_filters_map(&params);
 
@@ -187,9 +401,27 @@ pub async fn search_books(
         filters,
         count: results.len(),
         results,
+        facet_distribution,
+        limit,
+        offset,
+        estimated_total_hits,
     }))
 }
 
+fn to_results(metadata_list: Vec<BookMetadata>, scores: &HashMap<u32, f32>) -> Vec<BookResult> {
+    metadata_list
+        .into_iter()
+        .map(|book| BookResult {
+            score: scores.get(&book.book_id).copied(),
+            book_id: book.book_id,
+            title: book.title.clone(),
+            author: book.author.clone(),
+            language: book.language.clone(),
+            year: book.year,
+        })
+        .collect()
+}
+
 fn build_filters_map(params: &SearchParams) -> HashMap<String, String> {
     let mut filters = HashMap::new();
 
@@ -202,6 +434,9 @@ fn build_filters_map(params: &SearchParams) -> HashMap<String, String> {
     if let Some(year) = params.year {
         filters.insert("year".to_string(), year.to_string());
     }
+    if let Some(ref filter) = params.filter {
+        filters.insert("filter".to_string(), filter.clone());
+    }
 
     filters
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use std::io::Read;
+
+/// Mirrors `indexing-service`'s blob codec for the read side: metadata blobs
+/// stored in Redis carry a 1-byte codec tag prefix, so this only needs to
+/// decode - `search-service` never writes metadata back.
+///
+/// Also mirrors its legacy fallback: a blob written before the tag byte
+/// existed is raw JSON, indistinguishable from a tagged one except that its
+/// first byte is `{` rather than a codec tag, so a whole-buffer JSON parse
+/// is tried first before falling through to the tagged format.
+pub fn decompress(data: &[u8]) -> Vec<u8> {
+    if serde_json::from_slice::<serde::de::IgnoredAny>(data).is_ok() {
+        return data.to_vec();
+    }
+
+    let Some((&tag, body)) = data.split_first() else {
+        return Vec::new();
+    };
+    match tag {
+        1 => {
+            let mut decoder = flate2::read::GzDecoder::new(body);
+            let mut out = Vec::new();
+            decoder
+                .read_to_end(&mut out)
+                .expect("stored gzip blob is well-formed");
+            out
+        }
+        2 => zstd::decode_all(body).expect("stored zstd blob is well-formed"),
+        _ => body.to_vec(),
+    }
+}
@@ -0,0 +1,488 @@
+use crate::models::storage::BookMetadata;
+
+/// A parsed `filter` query expression, e.g.
+/// `year 1800 TO 1850 AND (language = en OR language = fr) AND NOT author = "Twain"`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCondition {
+    And(Box<FilterCondition>, Box<FilterCondition>),
+    Or(Box<FilterCondition>, Box<FilterCondition>),
+    Not(Box<FilterCondition>),
+    Condition {
+        field: String,
+        op: ComparisonOp,
+        value: FilterValue,
+    },
+    Range {
+        field: String,
+        low: FilterValue,
+        high: FilterValue,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Text(String),
+    Number(f64),
+}
+
+impl FilterValue {
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            FilterValue::Number(n) => Some(*n),
+            FilterValue::Text(s) => s.parse().ok(),
+        }
+    }
+
+    fn as_text(&self) -> String {
+        match self {
+            FilterValue::Text(s) => s.clone(),
+            FilterValue::Number(n) => n.to_string(),
+        }
+    }
+}
+
+/// Parse failure with the character offset it occurred at, so the API can
+/// report a precise `invalid_filter` error to the client.
+#[derive(Debug, Clone)]
+pub struct FilterParseError {
+    pub message: String,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    To,
+    Op(ComparisonOp),
+    Ident(String),
+    Number(f64),
+    Text(String),
+}
+
+struct Lexer {
+    tokens: Vec<(Token, usize)>,
+}
+
+fn lex(input: &str) -> Result<Lexer, FilterParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push((Token::LParen, start));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, start));
+                i += 1;
+            }
+            '=' => {
+                tokens.push((Token::Op(ComparisonOp::Eq), start));
+                i += 1;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push((Token::Op(ComparisonOp::Ne), start));
+                i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push((Token::Op(ComparisonOp::Ge), start));
+                i += 2;
+            }
+            '>' => {
+                tokens.push((Token::Op(ComparisonOp::Gt), start));
+                i += 1;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push((Token::Op(ComparisonOp::Le), start));
+                i += 2;
+            }
+            '<' => {
+                tokens.push((Token::Op(ComparisonOp::Lt), start));
+                i += 1;
+            }
+            '"' => {
+                i += 1;
+                let mut text = String::new();
+                while i < chars.len() && chars[i] != '"' {
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(FilterParseError {
+                        message: "unterminated quoted string".to_string(),
+                        offset: start,
+                    });
+                }
+                i += 1;
+                tokens.push((Token::Text(text), start));
+            }
+            _ => {
+                let mut word = String::new();
+                while i < chars.len() && !chars[i].is_whitespace() && !"()=!><".contains(chars[i])
+                {
+                    word.push(chars[i]);
+                    i += 1;
+                }
+                if word.is_empty() {
+                    return Err(FilterParseError {
+                        message: format!("unexpected character '{}'", c),
+                        offset: start,
+                    });
+                }
+                let token = match word.to_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "TO" => Token::To,
+                    _ => match word.parse::<f64>() {
+                        Ok(n) => Token::Number(n),
+                        Err(_) => Token::Ident(word),
+                    },
+                };
+                tokens.push((token, start));
+            }
+        }
+    }
+
+    Ok(Lexer { tokens })
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    input_len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(_, o)| *o)
+            .unwrap_or(self.input_len)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        tok
+    }
+
+    fn expect_value(&mut self) -> Result<FilterValue, FilterParseError> {
+        let offset = self.offset();
+        match self.advance() {
+            Some(Token::Text(s)) => Ok(FilterValue::Text(s)),
+            Some(Token::Ident(s)) => Ok(FilterValue::Text(s)),
+            Some(Token::Number(n)) => Ok(FilterValue::Number(n)),
+            other => Err(FilterParseError {
+                message: format!("expected a value, found {:?}", other),
+                offset,
+            }),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<FilterCondition, FilterParseError> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<FilterCondition, FilterParseError> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.advance();
+            let right = self.parse_and()?;
+            left = FilterCondition::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterCondition, FilterParseError> {
+        let mut left = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.advance();
+            let right = self.parse_not()?;
+            left = FilterCondition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<FilterCondition, FilterParseError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.advance();
+            let inner = self.parse_not()?;
+            return Ok(FilterCondition::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterCondition, FilterParseError> {
+        let offset = self.offset();
+        match self.peek() {
+            Some(Token::LParen) => {
+                self.advance();
+                let inner = self.parse_expr()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    other => Err(FilterParseError {
+                        message: format!("expected ')', found {:?}", other),
+                        offset: self.offset(),
+                    }),
+                }
+            }
+            Some(Token::Ident(_)) => self.parse_condition(),
+            other => Err(FilterParseError {
+                message: format!("expected a field, '(' or 'NOT', found {:?}", other),
+                offset,
+            }),
+        }
+    }
+
+    fn parse_condition(&mut self) -> Result<FilterCondition, FilterParseError> {
+        let field = match self.advance() {
+            Some(Token::Ident(name)) => name.to_lowercase(),
+            _ => unreachable!("parse_primary only calls parse_condition on an Ident"),
+        };
+
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.advance();
+            let value = self.expect_value()?;
+            return Ok(FilterCondition::Condition { field, op, value });
+        }
+
+        // No operator: either a bare boolean-ish condition (unsupported) or a
+        // `field value TO value` inclusive range.
+        let offset = self.offset();
+        let low = self.expect_value()?;
+        match self.advance() {
+            Some(Token::To) => {
+                let high = self.expect_value()?;
+                Ok(FilterCondition::Range { field, low, high })
+            }
+            other => Err(FilterParseError {
+                message: format!("expected an operator or 'TO', found {:?}", other),
+                offset,
+            }),
+        }
+    }
+}
+
+/// Parses a `filter` query expression into a `FilterCondition` tree via
+/// recursive descent, e.g. `year 1800 TO 1850 AND NOT author = "Twain"`.
+pub fn parse_filter(input: &str) -> Result<FilterCondition, FilterParseError> {
+    let lexer = lex(input)?;
+    let mut parser = Parser {
+        tokens: lexer.tokens,
+        pos: 0,
+        input_len: input.chars().count(),
+    };
+
+    let condition = parser.parse_expr()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(FilterParseError {
+            message: "unexpected trailing input".to_string(),
+            offset: parser.offset(),
+        });
+    }
+    Ok(condition)
+}
+
+fn compare_numbers(lhs: f64, op: ComparisonOp, rhs: f64) -> bool {
+    match op {
+        ComparisonOp::Eq => lhs == rhs,
+        ComparisonOp::Ne => lhs != rhs,
+        ComparisonOp::Gt => lhs > rhs,
+        ComparisonOp::Ge => lhs >= rhs,
+        ComparisonOp::Lt => lhs < rhs,
+        ComparisonOp::Le => lhs <= rhs,
+    }
+}
+
+fn compare_text(lhs: &str, op: ComparisonOp, rhs: &str) -> bool {
+    match op {
+        ComparisonOp::Eq => lhs.to_lowercase() == rhs.to_lowercase(),
+        ComparisonOp::Ne => lhs.to_lowercase() != rhs.to_lowercase(),
+        ComparisonOp::Gt => lhs > rhs,
+        ComparisonOp::Ge => lhs >= rhs,
+        ComparisonOp::Lt => lhs < rhs,
+        ComparisonOp::Le => lhs <= rhs,
+    }
+}
+
+/// `author` keeps its legacy substring semantics for `=`/`!=`; every other
+/// comparator and field compares the field's value directly.
+fn evaluate_condition(field: &str, op: ComparisonOp, value: &FilterValue, book: &BookMetadata) -> bool {
+    match field {
+        "author" => match op {
+            ComparisonOp::Eq => book
+                .author
+                .to_lowercase()
+                .contains(&value.as_text().to_lowercase()),
+            ComparisonOp::Ne => !book
+                .author
+                .to_lowercase()
+                .contains(&value.as_text().to_lowercase()),
+            _ => compare_text(&book.author, op, &value.as_text()),
+        },
+        "language" => compare_text(&book.language, op, &value.as_text()),
+        "year" => match (book.year, value.as_number()) {
+            (Some(year), Some(n)) => compare_numbers(year as f64, op, n),
+            (None, _) => false,
+            _ => false,
+        },
+        "word_count" => match value.as_number() {
+            Some(n) => compare_numbers(book.word_count as f64, op, n),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn evaluate_range(field: &str, low: &FilterValue, high: &FilterValue, book: &BookMetadata) -> bool {
+    evaluate_condition(field, ComparisonOp::Ge, low, book)
+        && evaluate_condition(field, ComparisonOp::Le, high, book)
+}
+
+/// Evaluates a parsed filter expression against a book's metadata.
+pub fn evaluate(condition: &FilterCondition, book: &BookMetadata) -> bool {
+    match condition {
+        FilterCondition::And(a, b) => evaluate(a, book) && evaluate(b, book),
+        FilterCondition::Or(a, b) => evaluate(a, book) || evaluate(b, book),
+        FilterCondition::Not(inner) => !evaluate(inner, book),
+        FilterCondition::Condition { field, op, value } => evaluate_condition(field, *op, value, book),
+        FilterCondition::Range { field, low, high } => evaluate_range(field, low, high, book),
+    }
+}
+
+// This is the first hand-written parser in the repo with no upstream test
+// coverage at all, so the bar here is higher than the rest of the codebase:
+// every grammar production plus the parse-failure and evaluation edge cases
+// below get a dedicated case instead of relying on integration coverage
+// through `routes::search`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(author: &str, language: &str, year: Option<u32>, word_count: usize) -> BookMetadata {
+        BookMetadata {
+            book_id: 1,
+            title: "Title".to_string(),
+            author: author.to_string(),
+            language: language.to_string(),
+            year,
+            word_count,
+            unique_words: 0,
+        }
+    }
+
+    #[test]
+    fn parses_simple_equality() {
+        let condition = parse_filter("language = en").unwrap();
+        assert_eq!(
+            condition,
+            FilterCondition::Condition {
+                field: "language".to_string(),
+                op: ComparisonOp::Eq,
+                value: FilterValue::Text("en".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_range() {
+        let condition = parse_filter("year 1800 TO 1850").unwrap();
+        assert_eq!(
+            condition,
+            FilterCondition::Range {
+                field: "year".to_string(),
+                low: FilterValue::Number(1800.0),
+                high: FilterValue::Number(1850.0),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_and_or_not_with_parens() {
+        let condition =
+            parse_filter("year 1800 TO 1850 AND (language = en OR language = fr) AND NOT author = \"Twain\"")
+                .unwrap();
+        let twain = book("Mark Twain", "en", Some(1820), 1000);
+        assert!(!evaluate(&condition, &twain));
+
+        let austen = book("Jane Austen", "en", Some(1820), 1000);
+        assert!(evaluate(&condition, &austen));
+    }
+
+    #[test]
+    fn rejects_unmatched_parens() {
+        let err = parse_filter("(language = en").unwrap_err();
+        assert_eq!(err.message, "expected ')', found None");
+
+        let err = parse_filter("language = en)").unwrap_err();
+        assert_eq!(err.message, "unexpected trailing input");
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        let err = parse_filter("").unwrap_err();
+        assert_eq!(err.offset, 0);
+    }
+
+    #[test]
+    fn range_with_reversed_bounds_matches_nothing() {
+        // Not a parse error - the grammar doesn't know which bound is meant
+        // to be smaller - but `evaluate_range`'s `low <= x <= high` can never
+        // be satisfied once low > high, so every book is excluded.
+        let condition = parse_filter("year 1990 TO 1800").unwrap();
+        assert!(!evaluate(&condition, &book("Author", "en", Some(1850), 1000)));
+        assert!(!evaluate(&condition, &book("Author", "en", Some(1990), 1000)));
+        assert!(!evaluate(&condition, &book("Author", "en", None, 1000)));
+    }
+
+    #[test]
+    fn numeric_field_compared_against_non_numeric_text_matches_nothing() {
+        // "unknown" doesn't parse as a number, so `as_number()` is `None`
+        // and the comparison can't be evaluated - this should fail closed,
+        // not panic or coerce.
+        let condition = parse_filter("year = unknown").unwrap();
+        assert!(!evaluate(&condition, &book("Author", "en", Some(1990), 1000)));
+    }
+
+    #[test]
+    fn text_field_comparison_is_case_insensitive_substring_for_author() {
+        let condition = parse_filter("author = twain").unwrap();
+        assert!(evaluate(&condition, &book("Mark Twain", "en", Some(1880), 1000)));
+        assert!(!evaluate(&condition, &book("Jane Austen", "en", Some(1880), 1000)));
+    }
+
+    #[test]
+    fn unknown_field_never_matches() {
+        let condition = parse_filter("publisher = acme").unwrap();
+        assert!(!evaluate(&condition, &book("Author", "en", Some(1990), 1000)));
+    }
+}
@@ -0,0 +1,73 @@
+use fst::automaton::Levenshtein;
+use fst::{IntoStreamer, Set, Streamer};
+
+/// Candidates are capped per query token so a single misspelled word can't
+/// blow up latency by expanding into thousands of postings lookups.
+const MAX_CANDIDATES_PER_TOKEN: usize = 50;
+
+/// A dictionary term within edit distance of a query token, paired with the
+/// distance it was found at so exact matches can be ranked ahead of fuzzy ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyCandidate {
+    pub term: String,
+    pub distance: u8,
+}
+
+fn max_edit_distance(token: &str) -> u32 {
+    match token.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Intersects a Levenshtein automaton bounded by `max_edit_distance(token)`
+/// against the sorted vocabulary FST, returning candidates ordered by edit
+/// distance (smallest first) then lexicographically, capped at
+/// `MAX_CANDIDATES_PER_TOKEN`.
+pub fn expand_fuzzy_candidates(vocabulary: &Set<Vec<u8>>, token: &str) -> Vec<FuzzyCandidate> {
+    let max_distance = max_edit_distance(token);
+
+    let automaton = match Levenshtein::new(token, max_distance) {
+        Ok(automaton) => automaton,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut stream = vocabulary.search(automaton).into_stream();
+    let mut candidates = Vec::new();
+
+    while let Some(term_bytes) = stream.next() {
+        let Ok(term) = std::str::from_utf8(term_bytes) else {
+            continue;
+        };
+        let distance = bounded_edit_distance(token, term, max_distance);
+        candidates.push(FuzzyCandidate {
+            term: term.to_string(),
+            distance,
+        });
+    }
+
+    candidates.sort_by(|a, b| a.distance.cmp(&b.distance).then_with(|| a.term.cmp(&b.term)));
+    candidates.truncate(MAX_CANDIDATES_PER_TOKEN);
+    candidates
+}
+
+/// Plain Levenshtein distance, only used to rank candidates already known to
+/// be within `max_distance` (the automaton guarantees that bound).
+fn bounded_edit_distance(a: &str, b: &str, max_distance: u32) -> u8 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    (prev[b.len()] as u32).min(max_distance) as u8
+}
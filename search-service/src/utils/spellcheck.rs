@@ -0,0 +1,106 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// Matches `indexing-service`'s delete-variant generation so a query term
+/// resolves against the same deletion-neighborhood dictionary it was built
+/// with.
+const MAX_EDIT_DISTANCE: usize = 2;
+
+/// Read side of `indexing-service`'s SymSpell-style deletion dictionary: a
+/// query term's own delete-variants are generated and looked up here to
+/// find candidate corrections without scanning the vocabulary.
+#[derive(Debug, Default, Deserialize)]
+pub struct CorrectionIndex {
+    pub deletions: HashMap<String, Vec<String>>,
+}
+
+pub fn deserialize(bytes: &[u8]) -> Result<CorrectionIndex, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
+
+fn delete_variants(term: &str, max_deletions: usize) -> HashSet<String> {
+    let mut variants = HashSet::new();
+    variants.insert(term.to_string());
+
+    let mut frontier = vec![term.to_string()];
+    for _ in 0..max_deletions {
+        let mut next = Vec::new();
+        for word in &frontier {
+            let chars: Vec<char> = word.chars().collect();
+            for i in 0..chars.len() {
+                let variant: String = chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| *idx != i)
+                    .map(|(_, c)| *c)
+                    .collect();
+                if variants.insert(variant.clone()) {
+                    next.push(variant);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    variants
+}
+
+/// Damerau-Levenshtein distance (optimal string alignment variant: adjacent
+/// transpositions count as one edit, but a transposed pair is not edited
+/// again), used to rank candidates the deletion
+/// dictionary surfaced (which only guarantees they're *reachable* within
+/// the delete budget, not that they're actually that close).
+fn damerau_levenshtein(a: &str, b: &str) -> u32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (la, lb) = (a.len(), b.len());
+
+    let mut d = vec![vec![0usize; lb + 1]; la + 1];
+    for (i, row) in d.iter_mut().enumerate().take(la + 1) {
+        row[0] = i;
+    }
+    for j in 0..=lb {
+        d[0][j] = j;
+    }
+
+    for i in 1..=la {
+        for j in 1..=lb {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[la][lb] as u32
+}
+
+impl CorrectionIndex {
+    /// Generates `term`'s delete-variants, unions the originating terms they
+    /// map to, and returns them paired with their true edit distance from
+    /// `term`, filtered to `MAX_EDIT_DISTANCE` and sorted closest-first.
+    pub fn correct(&self, term: &str) -> Vec<(String, u32)> {
+        let mut candidates: HashSet<String> = HashSet::new();
+        for variant in delete_variants(term, MAX_EDIT_DISTANCE) {
+            if let Some(terms) = self.deletions.get(&variant) {
+                candidates.extend(terms.iter().cloned());
+            }
+        }
+        candidates.remove(term);
+
+        let mut ranked: Vec<(String, u32)> = candidates
+            .into_iter()
+            .map(|candidate| {
+                let distance = damerau_levenshtein(term, &candidate);
+                (candidate, distance)
+            })
+            .filter(|(_, distance)| *distance as usize <= MAX_EDIT_DISTANCE)
+            .collect();
+
+        ranked.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+}
@@ -0,0 +1,13 @@
+/// Normalizes a search query the same way the indexer normalizes book text:
+/// lowercase, split on Unicode letter runs (or CJK-segment), drop stop words
+/// for `language`, then stem. Without this, a query for "running" would
+/// never match a body that was indexed as the stemmed term "run". Delegates
+/// to `common_tokenize` - the same crate `indexing-service` uses to
+/// normalize book text - so the two can't drift out of sync the way two
+/// hand-duplicated copies did.
+pub fn tokenize_query(query: &str, language: &str) -> Vec<String> {
+    common_tokenize::tokenize_with_positions(query, language)
+        .into_iter()
+        .map(|(term, _position)| term)
+        .collect()
+}